@@ -1,4 +1,8 @@
-use clap::{Parser, Subcommand};
+use crate::record_filter::Condition;
+use crate::table_commands::FileContent;
+use crate::terminal_output::DEFAULT_SAMPLE_ROWS;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
 
 /// bergr: A tool for inspecting Apache Iceberg tables
 #[derive(Parser, Debug)]
@@ -10,6 +14,51 @@ pub struct Cli {
     /// Enable debug logging
     #[arg(long, global = true)]
     pub debug: bool,
+
+    /// How to format output
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Json)]
+    pub format: OutputFormat,
+
+    /// Write output to a file instead of stdout
+    #[arg(long, global = true, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Only output these fields of each record (e.g. snapshot-id,summary.operation)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub fields: Vec<String>,
+
+    /// Only output records matching a comparison (e.g. 'summary.operation == "overwrite"');
+    /// may be repeated, and all must match
+    #[arg(long = "where", global = true, value_name = "EXPR")]
+    pub conditions: Vec<Condition>,
+
+    /// How many records table, CSV and TSV output read before fixing their columns; fields
+    /// first seen after them are left out, with a warning
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_SAMPLE_ROWS)]
+    pub sample_rows: usize,
+}
+
+/// Output formats supported by `TerminalOutput`
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Pretty-printed JSON for single objects, JSON Lines for lists
+    #[default]
+    Json,
+    /// Aligned columns for lists, key/value blocks for single objects.
+    /// Columns come from the first `--sample-rows` records
+    Table,
+    /// Comma-separated values, with nested fields flattened into dotted columns.
+    /// Columns come from the first `--sample-rows` records
+    Csv,
+    /// Tab-separated values, with nested fields flattened into dotted columns.
+    /// Columns come from the first `--sample-rows` records
+    Tsv,
+    /// YAML documents, separated by `---` for lists
+    Yaml,
+    /// A Parquet file, with a schema inferred from the records
+    Parquet,
+    /// An Arrow IPC file, with a schema inferred from the records
+    Arrow,
 }
 
 #[derive(Subcommand, Debug)]
@@ -52,12 +101,74 @@ pub enum TableCommands {
         /// The schema ID, or "current"
         schema_id: String,
     },
+    /// List all partition specs
+    PartitionSpecs,
+    /// Inspect a specific partition spec
+    PartitionSpec {
+        /// The partition spec ID, or "current"
+        spec_id: String,
+    },
+    /// List all sort orders
+    SortOrders,
+    /// Inspect a specific sort order
+    SortOrder {
+        /// The sort order ID, or "current"
+        order_id: String,
+    },
     /// List all snapshots
     Snapshots,
+    /// List all branches and tags
+    Refs,
+    /// Show how snapshots relate to each other and to the main line
+    Lineage,
+    /// Show the snapshot log
+    History,
+    /// List previous metadata files
+    MetadataLog {
+        /// Verify that previous metadata files exist
+        #[arg(long)]
+        verify: bool,
+    },
+    /// Report file sizes, manifest and delete file overhead, and maintenance recommendations
+    Health {
+        /// Data files smaller than this many bytes count as small (defaults to the table's
+        /// write.target-file-size-bytes, or 512 MiB)
+        #[arg(long)]
+        target_file_size: Option<u64>,
+    },
+    /// List files under the table location that no snapshot or metadata file references
+    Orphans {
+        /// Only report files last modified before this time (epoch millis, RFC 3339, or
+        /// relative like "-3d"), so that files from in-flight commits aren't reported
+        #[arg(long, allow_hyphen_values = true, default_value = "-3d")]
+        older_than: String,
+    },
+    /// Verify that the current snapshot's files exist, reporting which snapshots and refs
+    /// each missing file breaks
+    Verify {
+        /// Verify every retained snapshot instead of only the current one
+        #[arg(long, conflicts_with = "refs_only")]
+        all_snapshots: bool,
+        /// Verify the snapshots that branches and tags point to
+        #[arg(long)]
+        refs_only: bool,
+    },
+    /// Show files added and removed between two snapshots
+    Diff {
+        /// The snapshot to compare from (ID, "current", or a branch or tag)
+        from: String,
+        /// The snapshot to compare to (ID, "current", or a branch or tag)
+        to: String,
+    },
     /// Inspect a specific snapshot
     Snapshot {
-        /// The snapshot ID, or "current"
+        /// The snapshot ID, "current", "branch:<name>", "tag:<name>", a branch or tag name,
+        /// or "as-of" followed by a timestamp. Refs named like a snapshot ID, "current",
+        /// "as-of" or a subcommand (e.g. "info") need the "branch:" or "tag:" prefix
         snapshot_id: String,
+        /// For "as-of": an RFC 3339 timestamp, epoch milliseconds, or a relative time like "-2h"
+        #[arg(allow_hyphen_values = true)]
+        timestamp: Option<String>,
         #[command(subcommand)]
         command: SnapshotCmd,
     },
@@ -67,12 +178,58 @@ pub enum TableCommands {
 pub enum SnapshotCmd {
     /// Show snapshot details
     Info,
+    /// Walk the snapshot's parents back to the root
+    Ancestry,
+    /// List the manifests in the snapshot's manifest list
+    Manifests,
+    /// Summarize the snapshot's files and records per partition
+    Partitions,
     /// List files in the snapshot
-    Files {
-        /// Verify that data files exist
-        #[arg(long)]
-        verify: bool,
-    },
+    Files(FilesArgs),
+}
+
+#[derive(Args, Debug, Clone, Default)]
+pub struct FilesArgs {
+    /// Verify that data files exist
+    #[arg(long)]
+    pub verify: bool,
+    /// Verify that data files exist and match the size recorded in the manifest
+    #[arg(long)]
+    pub verify_size: bool,
+    /// Verify data files and check each Parquet footer's magic bytes, row count and schema
+    #[arg(long)]
+    pub deep: bool,
+    /// Write a JSON summary of the verification to this path (implies --verify)
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+    /// Include each data file's format, partition, sequence numbers and column statistics
+    #[arg(long)]
+    pub details: bool,
+    /// Which files to list from the snapshot's manifests
+    #[arg(long, value_enum, default_value_t = ContentFilter::All)]
+    pub content: ContentFilter,
+}
+
+/// Which manifests, and so which files, to include in a listing
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ContentFilter {
+    /// Data files only
+    Data,
+    /// Position deletes, equality deletes and deletion vectors only
+    Deletes,
+    /// Data and delete files
+    #[default]
+    All,
+}
+
+impl ContentFilter {
+    pub(crate) fn includes(self, content: FileContent) -> bool {
+        match self {
+            ContentFilter::Data => content == FileContent::Data,
+            ContentFilter::Deletes => content == FileContent::Deletes,
+            ContentFilter::All => true,
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
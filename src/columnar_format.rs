@@ -0,0 +1,425 @@
+//! Writes serialized records as Parquet or Arrow IPC for `--format parquet` and `--format arrow`.
+//!
+//! The Arrow schema is inferred the same way as for JSON Lines: nested objects become
+//! structs, arrays become lists, and fields with conflicting types become strings.
+//! Empty objects become nulls, as Parquet can't store a struct without fields, and
+//! fields that are objects in some records but not others hold JSON text.
+//!
+//! Records are spilled to a temporary file until the schema is known, then read back
+//! a batch at a time, so that large outputs aren't held in memory.
+
+use anyhow::Result;
+use arrow_array::RecordBatch;
+use arrow_ipc::writer::FileWriter;
+use arrow_json::ReaderBuilder;
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_schema::ArrowError;
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, IntoInnerError, Seek, Write};
+use std::sync::Arc;
+
+/// Rows in each record batch
+const BATCH_ROWS: usize = 8192;
+
+/// Rows in each Parquet row group, which is buffered until it's complete
+const ROW_GROUP_ROWS: usize = 16 * BATCH_ROWS;
+
+/// Separates the keys in a field path, as it won't appear in the keys themselves
+const PATH_SEPARATOR: char = '\0';
+
+/// Ends a field path to refer to the elements of the array there
+const ELEMENTS: &str = "\0[]";
+
+// The kinds of value seen at a field path, as bits
+const NULL: u8 = 1;
+const SCALAR: u8 = 2;
+const ARRAY: u8 = 4;
+const OBJECT: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnarFormat {
+    Parquet,
+    Arrow,
+}
+
+/// Writes records as a single Parquet or Arrow IPC file.
+///
+/// The schema covers every record's fields, so records are spilled to a temporary file
+/// as JSON Lines until `finish` is called. Records that aren't objects are written to a
+/// single `value` column.
+pub struct ColumnarWriter {
+    format: ColumnarFormat,
+    spill: Option<BufWriter<File>>,
+    /// The kinds of value seen at each field path, and in each array
+    shapes: HashMap<String, u8>,
+}
+
+impl ColumnarWriter {
+    pub fn new(format: ColumnarFormat) -> Self {
+        Self {
+            format,
+            spill: None,
+            shapes: HashMap::new(),
+        }
+    }
+
+    pub fn add_row(&mut self, record: Value) -> Result<()> {
+        let mut record = match record {
+            Value::Object(row) => Value::Object(row),
+            value => Value::Object(Map::from_iter([("value".to_string(), value)])),
+        };
+        if let Value::Object(row) = &mut record {
+            row.values_mut().for_each(null_empty_objects);
+        }
+        record_shapes(&mut self.shapes, &mut String::new(), &record);
+
+        let spill = match &mut self.spill {
+            Some(spill) => spill,
+            None => self.spill.insert(BufWriter::new(tempfile::tempfile()?)),
+        };
+        serde_json::to_writer(&mut *spill, &record)?;
+        spill.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Writes nothing if there are no records or fields, as there's no schema to infer
+    pub fn finish<W: Write>(self, writer: &mut W) -> Result<()> {
+        let Some(spill) = self.spill else {
+            return Ok(());
+        };
+        let mut spill = spill.into_inner().map_err(IntoInnerError::into_error)?;
+        let mixed: HashSet<&str> = self
+            .shapes
+            .keys()
+            .filter(|path| is_mixed(&self.shapes, path))
+            .map(String::as_str)
+            .collect();
+
+        let schema = Arc::new(infer_json_schema_from_iterator(
+            read_records(&mut spill, &mixed)?
+                .map(|record| record.map_err(|e| ArrowError::ExternalError(e.into()))),
+        )?);
+        if schema.fields().is_empty() {
+            return Ok(());
+        }
+        let mut decoder = ReaderBuilder::new(schema.clone())
+            .with_batch_size(BATCH_ROWS)
+            .with_coerce_primitive(true)
+            .build_decoder()?;
+        let mut records = read_records(&mut spill, &mixed)?;
+        let mut next_batch = || -> Result<Option<RecordBatch>> {
+            let rows = records
+                .by_ref()
+                .take(BATCH_ROWS)
+                .collect::<Result<Vec<Value>>>()?;
+            if rows.is_empty() {
+                return Ok(None);
+            }
+            decoder.serialize(&rows)?;
+            let batch = decoder.flush()?;
+            Ok(Some(
+                batch.unwrap_or_else(|| RecordBatch::new_empty(schema.clone())),
+            ))
+        };
+
+        match self.format {
+            ColumnarFormat::Parquet => {
+                // The Parquet writer needs a `Send` writer, so the file is built in
+                // another temporary file and copied out
+                let properties = WriterProperties::builder()
+                    .set_max_row_group_size(ROW_GROUP_ROWS)
+                    .build();
+                let mut parquet =
+                    ArrowWriter::try_new(tempfile::tempfile()?, schema.clone(), Some(properties))?;
+                while let Some(batch) = next_batch()? {
+                    parquet.write(&batch)?;
+                }
+                let mut file = parquet.into_inner()?;
+                file.rewind()?;
+                std::io::copy(&mut file, writer)?;
+            }
+            ColumnarFormat::Arrow => {
+                let mut arrow = FileWriter::try_new(writer, &schema)?;
+                while let Some(batch) = next_batch()? {
+                    arrow.write(&batch)?;
+                }
+                arrow.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the spilled records back from the start, with the values at mixed paths as JSON text.
+fn read_records<'a>(
+    spill: &'a mut File,
+    mixed: &'a HashSet<&str>,
+) -> Result<impl Iterator<Item = Result<Value>> + 'a> {
+    spill.rewind()?;
+    let records = serde_json::Deserializer::from_reader(BufReader::new(spill)).into_iter();
+    Ok(records.map(move |record| {
+        let mut record = record?;
+        if !mixed.is_empty() {
+            stringify_mixed(mixed, &mut String::new(), &mut record);
+        }
+        Ok(record)
+    }))
+}
+
+/// Replaces empty objects, at any depth, with nulls.
+fn null_empty_objects(value: &mut Value) {
+    match value {
+        Value::Object(map) if map.is_empty() => *value = Value::Null,
+        Value::Object(map) => map.values_mut().for_each(null_empty_objects),
+        Value::Array(items) => items.iter_mut().for_each(null_empty_objects),
+        _ => {}
+    }
+}
+
+/// Notes the kind of value at `path`, and at every path beneath it.
+fn record_shapes(shapes: &mut HashMap<String, u8>, path: &mut String, value: &Value) {
+    let shape = match value {
+        Value::Null => NULL,
+        Value::Array(_) => ARRAY,
+        Value::Object(_) => OBJECT,
+        _ => SCALAR,
+    };
+    match shapes.get_mut(path.as_str()) {
+        Some(seen) => *seen |= shape,
+        None => {
+            shapes.insert(path.clone(), shape);
+        }
+    }
+
+    let len = path.len();
+    match value {
+        Value::Array(items) => {
+            path.push_str(ELEMENTS);
+            for item in items {
+                record_shapes(shapes, path, item);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                path.truncate(len);
+                path.push(PATH_SEPARATOR);
+                path.push_str(key);
+                record_shapes(shapes, path, item);
+            }
+        }
+        _ => {}
+    }
+    path.truncate(len);
+}
+
+/// Whether the values at a path mix kinds that schema inference can't reconcile.
+///
+/// A field may hold scalars alongside arrays of scalars, but objects only alongside
+/// nulls. Array elements have to be all objects, all arrays, or scalars and nulls.
+fn is_mixed(shapes: &HashMap<String, u8>, path: &str) -> bool {
+    let shape = shapes[path];
+    if path.ends_with(ELEMENTS) {
+        return !(shape & !(SCALAR | NULL) == 0 || shape == OBJECT || shape == ARRAY);
+    }
+    match shape & !NULL {
+        0 | SCALAR | ARRAY | OBJECT => false,
+        shape if shape == SCALAR | ARRAY => {
+            let elements = shapes
+                .get(&format!("{}{}", path, ELEMENTS))
+                .copied()
+                .unwrap_or_default();
+            elements == OBJECT || elements == ARRAY
+        }
+        _ => true,
+    }
+}
+
+/// Replaces the values at mixed paths with their JSON text, leaving strings and nulls as they are.
+fn stringify_mixed(mixed: &HashSet<&str>, path: &mut String, value: &mut Value) {
+    if mixed.contains(path.as_str()) {
+        if !value.is_null() && !value.is_string() {
+            *value = Value::String(value.to_string());
+        }
+        return;
+    }
+
+    let len = path.len();
+    match value {
+        Value::Array(items) => {
+            path.push_str(ELEMENTS);
+            for item in items {
+                stringify_mixed(mixed, path, item);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                path.truncate(len);
+                path.push(PATH_SEPARATOR);
+                path.push_str(key);
+                stringify_mixed(mixed, path, item);
+            }
+        }
+        _ => {}
+    }
+    path.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use arrow_ipc::reader::FileReader;
+    use bytes::Bytes;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn render(format: ColumnarFormat, records: &[Value]) -> Vec<u8> {
+        let mut writer = ColumnarWriter::new(format);
+        for record in records {
+            writer.add_row(record.clone()).unwrap();
+        }
+        let mut buffer = Vec::new();
+        writer.finish(&mut buffer).unwrap();
+        buffer
+    }
+
+    fn records() -> Vec<Value> {
+        vec![
+            json!({"snapshot-id": 1, "summary": {"operation": "append"}}),
+            json!({
+                "snapshot-id": 2,
+                "parent": 1,
+                "summary": {"operation": "overwrite"},
+                "ids": [1, 2],
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_parquet() {
+        let buffer = render(ColumnarFormat::Parquet, &records());
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        let schema = batch.schema();
+        let names: Vec<&str> = schema.fields().iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, ["snapshot-id", "summary", "parent", "ids"]);
+        let summaries = batch.column(1).as_struct();
+        assert_eq!(summaries.column(0).as_string::<i32>().value(1), "overwrite");
+        let parents = batch.column(2).as_primitive::<Int64Type>();
+        assert!(parents.is_null(0));
+        assert_eq!(parents.value(1), 1);
+    }
+
+    #[test]
+    fn test_arrow_with_conflicting_types() {
+        let buffer = render(
+            ColumnarFormat::Arrow,
+            &[json!({"value": 1}), json!({"value": "two"}), json!("three")],
+        );
+        let reader = FileReader::try_new(Cursor::new(buffer), None).unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+
+        let values = batches[0].column(0).as_string::<i32>();
+        let values: Vec<&str> = values.iter().map(|v| v.unwrap()).collect();
+        assert_eq!(values, ["1", "two", "three"]);
+    }
+
+    #[test]
+    fn test_records_read_back_in_batches() {
+        let records: Vec<Value> = (0..BATCH_ROWS + 1)
+            .map(|i| json!({"id": i, "extra": (i == BATCH_ROWS).then_some("last")}))
+            .collect();
+        let buffer = render(ColumnarFormat::Parquet, &records);
+        let reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|batch| batch.unwrap()).collect();
+
+        let rows: usize = batches.iter().map(RecordBatch::num_rows).sum();
+        assert_eq!(rows, BATCH_ROWS + 1);
+        let last = batches.last().unwrap();
+        let extras = last.column(1).as_string::<i32>();
+        assert_eq!(extras.value(last.num_rows() - 1), "last");
+    }
+
+    #[test]
+    fn test_empty_and_null_columns() {
+        assert!(render(ColumnarFormat::Parquet, &[]).is_empty());
+        assert!(render(ColumnarFormat::Parquet, &[json!({})]).is_empty());
+        assert!(!render(ColumnarFormat::Parquet, &[json!({"parent": null})]).is_empty());
+    }
+
+    fn read_parquet(buffer: Vec<u8>) -> RecordBatch {
+        let mut reader = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(buffer))
+            .unwrap()
+            .build()
+            .unwrap();
+        reader.next().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_empty_objects() {
+        let batch = read_parquet(render(
+            ColumnarFormat::Parquet,
+            &[
+                json!({"path": "a.parquet", "partition": {}, "summary": {"extra": {}}}),
+                json!({"path": "b.parquet", "partition": {}, "summary": {}}),
+            ],
+        ));
+
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.column(1).logical_null_count(), 2);
+        let summaries = batch.column(2).as_struct();
+        assert!(summaries.is_null(1));
+        assert_eq!(summaries.column(0).logical_null_count(), 2);
+    }
+
+    #[test]
+    fn test_mixed_shape_field() {
+        let batch = read_parquet(render(
+            ColumnarFormat::Parquet,
+            &[
+                json!({"type": "long", "fields": [{"name": "id", "type": "long"}]}),
+                json!({
+                    "type": {"type": "list", "element": "string"},
+                    "fields": [
+                        {"name": "tags", "type": {"type": "list", "element": "string"}},
+                        {"name": "count", "type": null},
+                    ],
+                }),
+            ],
+        ));
+
+        let types = batch.column(0).as_string::<i32>();
+        assert_eq!(types.value(0), "long");
+        assert_eq!(types.value(1), r#"{"type":"list","element":"string"}"#);
+
+        // Fields of objects in arrays are handled the same way
+        let fields = batch.column(1).as_list::<i32>();
+        let types = fields.values().as_struct().column(1).as_string::<i32>();
+        let types: Vec<Option<&str>> = types.iter().collect();
+        assert_eq!(
+            types,
+            [
+                Some("long"),
+                Some(r#"{"type":"list","element":"string"}"#),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Integrity checks for Parquet data files, beyond existence.
+//!
+//! Reads only each file's footer: the magic bytes at both ends, the footer
+//! metadata, its row count, and the field IDs and column types of its schema.
+
+use anyhow::Result;
+use iceberg::io::FileIO;
+use iceberg::spec::{
+    DataFileFormat, ManifestEntryRef, PrimitiveType, TableMetadata, Type as IcebergType,
+};
+use parquet::basic::Type as PhysicalType;
+use parquet::file::metadata::ParquetMetaDataReader;
+use parquet::schema::types::{Type as ParquetType, TypePtr};
+use std::collections::HashSet;
+
+const PARQUET_MAGIC: &[u8; 4] = b"PAR1";
+
+/// Footer length (4 bytes) plus trailing magic (4 bytes)
+const FOOTER_TAIL_SIZE: u64 = 8;
+
+/// Files that rely on name mapping instead of field IDs (e.g. migrated from Hive) set this
+const NAME_MAPPING_PROPERTY: &str = "schema.name-mapping.default";
+
+/// Checks a data file's Parquet footer against its manifest entry and the table schemas.
+///
+/// Returns a description of each problem found; an empty list means the file
+/// passed. Only errors that prevent checking (e.g. credentials) are returned as `Err`.
+/// Files in other formats aren't checked.
+pub async fn check_data_file(
+    file_io: &FileIO,
+    entry: &ManifestEntryRef,
+    metadata: &TableMetadata,
+) -> Result<Vec<String>> {
+    if entry.file_format() != DataFileFormat::Parquet {
+        return Ok(Vec::new());
+    }
+
+    let input = file_io.new_input(entry.file_path())?;
+    let size = input.metadata().await?.size;
+    if size < PARQUET_MAGIC.len() as u64 + FOOTER_TAIL_SIZE {
+        return Ok(vec![format!(
+            "file is too small to be Parquet ({} bytes)",
+            size
+        )]);
+    }
+    let reader = input.reader().await?;
+
+    let mut failures = Vec::new();
+    if reader.read(0..4).await?.as_ref() != PARQUET_MAGIC {
+        failures.push("missing Parquet magic bytes at start of file".to_string());
+    }
+    let tail = reader.read(size - FOOTER_TAIL_SIZE..size).await?;
+    if &tail[4..] != PARQUET_MAGIC {
+        failures.push("missing Parquet magic bytes at end of file".to_string());
+        return Ok(failures);
+    }
+
+    let footer_length = u32::from_le_bytes([tail[0], tail[1], tail[2], tail[3]]) as u64;
+    if footer_length + FOOTER_TAIL_SIZE + PARQUET_MAGIC.len() as u64 > size {
+        failures.push(format!(
+            "footer length {} exceeds file size {}",
+            footer_length, size
+        ));
+        return Ok(failures);
+    }
+    let footer_start = size - FOOTER_TAIL_SIZE - footer_length;
+    let footer = reader.read(footer_start..size - FOOTER_TAIL_SIZE).await?;
+    let parquet_metadata = match ParquetMetaDataReader::decode_metadata(&footer) {
+        Ok(parquet_metadata) => parquet_metadata,
+        Err(e) => {
+            failures.push(format!("invalid footer: {}", e));
+            return Ok(failures);
+        }
+    };
+
+    let file_metadata = parquet_metadata.file_metadata();
+    let row_count = file_metadata.num_rows();
+    if u64::try_from(row_count).ok() != Some(entry.record_count()) {
+        failures.push(format!(
+            "footer has {} rows but the manifest records {}",
+            row_count,
+            entry.record_count()
+        ));
+    }
+
+    if !metadata.properties().contains_key(NAME_MAPPING_PROPERTY) {
+        failures.extend(check_field_ids(file_metadata.schema(), entry, metadata));
+    }
+
+    Ok(failures)
+}
+
+/// Checks that the file's columns carry field IDs the table knows about, with
+/// types compatible with the table schema.
+///
+/// Each column is compared with the field of the same ID in the current schema,
+/// or in the schema the file was written with if the column has since been dropped.
+fn check_field_ids(
+    file_schema: &ParquetType,
+    entry: &ManifestEntryRef,
+    metadata: &TableMetadata,
+) -> Vec<String> {
+    let mut columns = Vec::new();
+    collect_columns(file_schema.get_fields(), &mut columns);
+    if columns.is_empty() {
+        return vec!["file schema has no field IDs".to_string()];
+    }
+
+    let written_schema = entry
+        .snapshot_id()
+        .and_then(|snapshot_id| metadata.snapshot_by_id(snapshot_id))
+        .and_then(|snapshot| snapshot.schema_id())
+        .and_then(|schema_id| metadata.schema_by_id(schema_id));
+    // Columns may also have been dropped before the snapshot that added the file, so
+    // an ID in any schema counts as known
+    let known: HashSet<i32> = metadata
+        .schemas_iter()
+        .flat_map(|schema| schema.field_id_to_fields().keys().copied())
+        .collect();
+
+    let mut failures = Vec::new();
+    let mut unknown = Vec::new();
+    for (field_id, column) in columns {
+        let field = metadata
+            .current_schema()
+            .field_by_id(field_id)
+            .or_else(|| written_schema.and_then(|schema| schema.field_by_id(field_id)));
+        match field {
+            Some(field) if !is_compatible(column, &field.field_type) => {
+                let file_type = if column.is_group() {
+                    "a group".to_string()
+                } else {
+                    column.get_physical_type().to_string()
+                };
+                failures.push(format!(
+                    "column {} (field ID {}) is {} in the file but {} in the table schema",
+                    column.name(),
+                    field_id,
+                    file_type,
+                    field.field_type
+                ));
+            }
+            Some(_) => {}
+            None if known.contains(&field_id) => {}
+            None => unknown.push(field_id.to_string()),
+        }
+    }
+    if !unknown.is_empty() {
+        failures.insert(
+            0,
+            format!(
+                "file schema has field IDs not in any table schema: {}",
+                unknown.join(", ")
+            ),
+        );
+    }
+    failures
+}
+
+/// Whether a Parquet column can hold values of an Iceberg type.
+///
+/// Only physical types are compared, allowing for Iceberg's type promotions
+/// (int to long, float to double, and widening decimals).
+fn is_compatible(column: &ParquetType, field_type: &IcebergType) -> bool {
+    let IcebergType::Primitive(primitive) = field_type else {
+        return column.is_group();
+    };
+    if column.is_group() {
+        return false;
+    }
+    let physical = column.get_physical_type();
+    match primitive {
+        PrimitiveType::Boolean => physical == PhysicalType::BOOLEAN,
+        PrimitiveType::Int | PrimitiveType::Date => physical == PhysicalType::INT32,
+        PrimitiveType::Long => matches!(physical, PhysicalType::INT32 | PhysicalType::INT64),
+        PrimitiveType::Float => physical == PhysicalType::FLOAT,
+        PrimitiveType::Double => matches!(physical, PhysicalType::FLOAT | PhysicalType::DOUBLE),
+        PrimitiveType::Time | PrimitiveType::TimestampNs | PrimitiveType::TimestamptzNs => {
+            physical == PhysicalType::INT64
+        }
+        // Older writers stored timestamps as INT96
+        PrimitiveType::Timestamp | PrimitiveType::Timestamptz => {
+            matches!(physical, PhysicalType::INT64 | PhysicalType::INT96)
+        }
+        PrimitiveType::String => physical == PhysicalType::BYTE_ARRAY,
+        PrimitiveType::Uuid | PrimitiveType::Fixed(_) => {
+            physical == PhysicalType::FIXED_LEN_BYTE_ARRAY
+        }
+        PrimitiveType::Binary => matches!(
+            physical,
+            PhysicalType::BYTE_ARRAY | PhysicalType::FIXED_LEN_BYTE_ARRAY
+        ),
+        PrimitiveType::Decimal { .. } => matches!(
+            physical,
+            PhysicalType::INT32
+                | PhysicalType::INT64
+                | PhysicalType::FIXED_LEN_BYTE_ARRAY
+                | PhysicalType::BYTE_ARRAY
+        ),
+    }
+}
+
+/// Collects the columns that carry field IDs, including those nested in groups.
+fn collect_columns<'a>(fields: &'a [TypePtr], columns: &mut Vec<(i32, &'a ParquetType)>) {
+    for field in fields {
+        let info = field.get_basic_info();
+        if info.has_id() {
+            columns.push((info.id(), field.as_ref()));
+        }
+        if field.is_group() {
+            collect_columns(field.get_fields(), columns);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::table_commands::load_table;
+    use crate::test_support::*;
+    use iceberg::spec::{ManifestEntry, ManifestStatus};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    /// Returns a Parquet file with no rows and the given schema
+    fn empty_parquet_file(message_type: &str) -> Vec<u8> {
+        let schema = Arc::new(parse_message_type(message_type).unwrap());
+        let mut buffer = Vec::new();
+        let writer = SerializedFileWriter::new(
+            &mut buffer,
+            schema,
+            Arc::new(WriterProperties::builder().build()),
+        )
+        .unwrap();
+        writer.close().unwrap();
+        buffer
+    }
+
+    async fn check(content: Vec<u8>, record_count: u64) -> Result<Vec<String>> {
+        let path = "s3://bucket/table/data/a.parquet";
+        let metadata_json = serde_json::to_string(&empty_metadata())?;
+        let file_io =
+            create_memory_file_io(vec![("s3://bucket/table/metadata.json", &metadata_json)]).await;
+        file_io.new_output(path)?.write(content.into()).await?;
+        let table = load_table(&file_io, "s3://bucket/table/metadata.json").await?;
+
+        let entry = ManifestEntry::builder()
+            .status(ManifestStatus::Added)
+            .data_file(data_file(path, record_count, 0))
+            .build();
+        check_data_file(&file_io, &Arc::new(entry), table.metadata()).await
+    }
+
+    #[tokio::test]
+    async fn test_valid_file() -> Result<()> {
+        let content = empty_parquet_file(
+            "message table { required int32 id = 1; optional int32 event_date (DATE) = 2; }",
+        );
+        assert_eq!(check(content, 0).await?, Vec::<String>::new());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_row_count_mismatch() -> Result<()> {
+        let content = empty_parquet_file("message table { required int32 id = 1; }");
+        assert_eq!(
+            check(content, 10).await?,
+            vec!["footer has 0 rows but the manifest records 10"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unknown_field_ids() -> Result<()> {
+        let content = empty_parquet_file(
+            "message table { required int32 id = 1; required int32 other = 99; }",
+        );
+        assert_eq!(
+            check(content, 0).await?,
+            vec!["file schema has field IDs not in any table schema: 99"]
+        );
+
+        let content = empty_parquet_file("message table { required int32 id; }");
+        assert_eq!(
+            check(content, 0).await?,
+            vec!["file schema has no field IDs"]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_incompatible_types() -> Result<()> {
+        let content = empty_parquet_file(
+            "message table { required binary id (UTF8) = 1; optional int32 event_date (DATE) = 2; }",
+        );
+        assert_eq!(
+            check(content, 0).await?,
+            vec!["column id (field ID 1) is BYTE_ARRAY in the file but int in the table schema"]
+        );
+
+        let content = empty_parquet_file(
+            "message table { required int32 id = 1; optional group event_date = 2 { optional int32 day; } }",
+        );
+        assert_eq!(
+            check(content, 0).await?,
+            vec![
+                "column event_date (field ID 2) is a group in the file but date in the table schema"
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_not_parquet() -> Result<()> {
+        assert_eq!(
+            check(b"PAR1 but truncated".to_vec(), 0).await?,
+            vec!["missing Parquet magic bytes at end of file"]
+        );
+        assert_eq!(
+            check(b"tiny".to_vec(), 0).await?,
+            vec!["file is too small to be Parquet (4 bytes)"]
+        );
+        Ok(())
+    }
+}
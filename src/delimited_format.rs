@@ -0,0 +1,206 @@
+//! Renders serialized records as CSV or TSV for `--format csv` and `--format tsv`.
+//!
+//! Nested objects are flattened into dotted column names, e.g. `summary.operation`,
+//! so that each record is a single row.
+
+use crate::terminal_output::DroppedColumns;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+/// Writes a stream of records as delimited rows under a header.
+///
+/// The header is the union of the columns in the first `sample_rows` records, which
+/// are held until it's written; later rows are written as they arrive, and columns
+/// that only appear in them are left out with a warning.
+pub struct DelimitedWriter {
+    delimiter: u8,
+    sample_rows: usize,
+    pending: Vec<Map<String, Value>>,
+    columns: Option<Vec<String>>,
+    dropped: DroppedColumns,
+}
+
+impl DelimitedWriter {
+    pub fn new(delimiter: u8, sample_rows: usize) -> Self {
+        Self {
+            delimiter,
+            sample_rows,
+            pending: Vec::new(),
+            columns: None,
+            dropped: DroppedColumns::new(sample_rows),
+        }
+    }
+
+    pub fn write_row<W: Write>(&mut self, writer: &mut W, record: &Value) -> Result<()> {
+        let row = flatten(record);
+        let Some(columns) = &self.columns else {
+            self.pending.push(row);
+            if self.pending.len() >= self.sample_rows {
+                self.flush(writer)?;
+            }
+            return Ok(());
+        };
+        for name in row.keys().filter(|name| !columns.contains(name)) {
+            self.dropped.add(name);
+        }
+        self.write_rows(writer, columns, &[row])
+    }
+
+    /// Writes any rows still held back for the header.
+    pub fn finish<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        if self.columns.is_none() && !self.pending.is_empty() {
+            self.flush(writer)?;
+        }
+        Ok(())
+    }
+
+    fn flush<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        let mut columns: Vec<String> = Vec::new();
+        for row in &self.pending {
+            for column in row.keys() {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            }
+        }
+
+        let header = columns
+            .iter()
+            .map(|column| (column.clone(), Value::String(column.clone())))
+            .collect();
+        let rows = std::mem::take(&mut self.pending);
+        self.write_rows(writer, &columns, &[header])?;
+        self.write_rows(writer, &columns, &rows)?;
+        self.columns = Some(columns);
+        Ok(())
+    }
+
+    fn write_rows<W: Write>(
+        &self,
+        writer: &mut W,
+        columns: &[String],
+        rows: &[Map<String, Value>],
+    ) -> Result<()> {
+        let mut csv = csv::WriterBuilder::new()
+            .delimiter(self.delimiter)
+            .from_writer(writer);
+        for row in rows {
+            csv.write_record(
+                columns
+                    .iter()
+                    .map(|column| row.get(column).map(cell_text).unwrap_or_default()),
+            )?;
+        }
+        csv.flush()?;
+        Ok(())
+    }
+}
+
+/// Flattens nested objects into a single level, joining keys with dots.
+///
+/// Arrays are kept whole, and values that aren't objects go in a `value` column.
+pub fn flatten(value: &Value) -> Map<String, Value> {
+    let mut flattened = Map::new();
+    match value {
+        Value::Object(map) => flatten_into(map, "", &mut flattened),
+        value => {
+            flattened.insert("value".to_string(), value.clone());
+        }
+    }
+    flattened
+}
+
+fn flatten_into(map: &Map<String, Value>, prefix: &str, flattened: &mut Map<String, Value>) {
+    for (key, value) in map {
+        let name = format!("{}{}", prefix, key);
+        match value {
+            Value::Object(nested) => flatten_into(nested, &format!("{}.", name), flattened),
+            value => {
+                flattened.insert(name, value.clone());
+            }
+        }
+    }
+}
+
+/// Shows strings without quotes, nulls as empty cells, and arrays as compact JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal_output::DEFAULT_SAMPLE_ROWS;
+    use serde_json::json;
+
+    fn render(delimiter: u8, records: &[Value]) -> String {
+        let mut writer = DelimitedWriter::new(delimiter, DEFAULT_SAMPLE_ROWS);
+        let mut buffer = Vec::new();
+        for record in records {
+            writer.write_row(&mut buffer, record).unwrap();
+        }
+        writer.finish(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_csv_flattens_and_unions_columns() {
+        let output = render(
+            b',',
+            &[
+                json!({"snapshot-id": 1, "summary": {"operation": "append"}}),
+                json!({"snapshot-id": 2, "parent": 1, "summary": {"operation": "overwrite, partial"}}),
+            ],
+        );
+
+        assert_eq!(
+            output,
+            "snapshot-id,summary.operation,parent\n\
+             1,append,\n\
+             2,\"overwrite, partial\",1\n"
+        );
+    }
+
+    #[test]
+    fn test_rows_stream_once_header_is_written() {
+        let mut writer = DelimitedWriter::new(b',', 2);
+        let mut buffer = Vec::new();
+        writer.write_row(&mut buffer, &json!({"id": 0})).unwrap();
+        writer
+            .write_row(&mut buffer, &json!({"id": 1, "parent": 0}))
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&buffer), "id,parent\n0,\n1,0\n");
+
+        // Rows after the header are written straight away, and columns that only
+        // appear in them are reported as left out
+        writer
+            .write_row(&mut buffer, &json!({"id": 2, "extra": true}))
+            .unwrap();
+        writer
+            .write_row(&mut buffer, &json!({"id": 3, "extra": false, "more": 1}))
+            .unwrap();
+        writer.finish(&mut buffer).unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "id,parent\n0,\n1,0\n2,\n3,\n"
+        );
+        assert_eq!(writer.dropped.names, ["extra", "more"]);
+    }
+
+    #[test]
+    fn test_tsv() {
+        let output = render(b'\t', &[json!({"path": "a.parquet", "ids": [1, 2]})]);
+        assert_eq!(output, "path\tids\na.parquet\t[1,2]\n");
+    }
+
+    #[test]
+    fn test_scalars_and_empty() {
+        assert_eq!(render(b',', &[json!("db.a")]), "value\ndb.a\n");
+        assert_eq!(render(b',', &[]), "");
+    }
+}
@@ -1,13 +1,19 @@
 //! File existence checking with optimized implementations.
 //!
 //! Provides a trait for checking file existence, with implementations that
-//! either delegate to FileIO or use a pre-loaded set of known locations.
+//! delegate to FileIO, use a pre-loaded set of known locations, or list
+//! each file's directory on demand.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use aws_sdk_s3::Client;
+use aws_sdk_s3::types::Object;
 use iceberg::io::FileIO;
-use std::collections::HashSet;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
 use tracing::debug;
 use tracing::info;
 
@@ -16,6 +22,12 @@ use tracing::info;
 pub trait FileExistenceChecker: Send + Sync {
     /// Checks if a file exists at the given path.
     async fn exists(&self, path: &str) -> Result<bool>;
+
+    /// Returns the size of the file at the given path, or `None` if it doesn't exist.
+    async fn size(&self, path: &str) -> Result<Option<u64>>;
+
+    /// Short name for how this checker has found files so far, for reports.
+    fn strategy(&self) -> &'static str;
 }
 
 /// Checks file existence by delegating to FileIO.
@@ -34,56 +46,313 @@ impl FileExistenceChecker for FileIOExistenceChecker {
     async fn exists(&self, path: &str) -> Result<bool> {
         Ok(self.file_io.exists(path).await?)
     }
+
+    async fn size(&self, path: &str) -> Result<Option<u64>> {
+        // Fetching metadata for a missing file is an error we can't tell apart
+        // from other failures, so check existence first
+        if !self.exists(path).await? {
+            return Ok(None);
+        }
+        let metadata = self.file_io.new_input(path)?.metadata().await?;
+        Ok(Some(metadata.size))
+    }
+
+    fn strategy(&self) -> &'static str {
+        "file-io"
+    }
 }
 
-/// Checks file existence against a pre-loaded set of known locations.
+/// Checks file existence against a pre-loaded set of known locations and their sizes.
 ///
 /// Stores only the suffix of each path (after stripping the common base URL prefix)
-/// to reduce memory usage when there are many files.
+/// to reduce memory usage when there are many files. Files outside the base URL,
+/// e.g. written under a `write.data.path` override or added from elsewhere, are
+/// checked by a `FallbackExistenceChecker`.
 pub struct PreloadedExistenceChecker {
     base_url: String,
-    suffixes: HashSet<Box<str>>,
+    sizes: HashMap<Box<str>, u64>,
+    fallback: FallbackExistenceChecker,
 }
 
 impl PreloadedExistenceChecker {
-    fn new(base_url: String, suffixes: HashSet<Box<str>>) -> Self {
-        Self { base_url, suffixes }
+    fn new(
+        base_url: String,
+        sizes: HashMap<Box<str>, u64>,
+        fallback: FallbackExistenceChecker,
+    ) -> Self {
+        Self {
+            base_url,
+            sizes,
+            fallback,
+        }
     }
 }
 
 #[async_trait]
 impl FileExistenceChecker for PreloadedExistenceChecker {
     async fn exists(&self, path: &str) -> Result<bool> {
-        Ok(path
-            .strip_prefix(&self.base_url)
-            .is_some_and(|suffix| self.suffixes.contains(suffix)))
+        match path.strip_prefix(&self.base_url) {
+            Some(suffix) => Ok(self.sizes.contains_key(suffix)),
+            None => self.fallback.exists(path).await,
+        }
+    }
+
+    async fn size(&self, path: &str) -> Result<Option<u64>> {
+        match path.strip_prefix(&self.base_url) {
+            Some(suffix) => Ok(self.sizes.get(suffix).copied()),
+            None => self.fallback.size(path).await,
+        }
+    }
+
+    fn strategy(&self) -> &'static str {
+        match (self.fallback.headed(), self.fallback.listed()) {
+            (false, false) => "s3-listing",
+            (true, false) => "s3-listing+head",
+            (false, true) => "s3-listing+directory-listing",
+            (true, true) => "s3-listing+head+directory-listing",
+        }
+    }
+}
+
+/// Files checked individually in a directory before `FallbackExistenceChecker` lists it instead
+const HEAD_REQUESTS_PER_DIRECTORY: usize = 16;
+
+/// Checks files that a preloaded listing doesn't cover.
+///
+/// Starts with a HEAD request per file, but once a directory on S3 has had
+/// `HEAD_REQUESTS_PER_DIRECTORY` files checked, lists it to check the rest in bulk.
+/// Only the most recently checked directories are counted, as with listings.
+pub struct FallbackExistenceChecker {
+    file_io: FileIOExistenceChecker,
+    directories: Option<DirectoryListingExistenceChecker>,
+    checks_per_directory: Mutex<DirectoryCache<usize>>,
+    headed: AtomicBool,
+    listed: AtomicBool,
+}
+
+impl FallbackExistenceChecker {
+    fn new(file_io: FileIO, s3_client: Option<&Client>) -> Self {
+        Self {
+            file_io: FileIOExistenceChecker::new(file_io.clone()),
+            directories: s3_client
+                .map(|client| DirectoryListingExistenceChecker::new(client.clone(), file_io)),
+            checks_per_directory: Mutex::new(DirectoryCache::new(CACHED_DIRECTORIES)),
+            headed: AtomicBool::new(false),
+            listed: AtomicBool::new(false),
+        }
+    }
+
+    async fn exists(&self, path: &str) -> Result<bool> {
+        match self.directory_checker(path) {
+            Some(directories) => {
+                self.listed.store(true, Ordering::Relaxed);
+                directories.exists(path).await
+            }
+            None => {
+                self.headed.store(true, Ordering::Relaxed);
+                self.file_io.exists(path).await
+            }
+        }
+    }
+
+    async fn size(&self, path: &str) -> Result<Option<u64>> {
+        match self.directory_checker(path) {
+            Some(directories) => {
+                self.listed.store(true, Ordering::Relaxed);
+                directories.size(path).await
+            }
+            None => {
+                self.headed.store(true, Ordering::Relaxed);
+                self.file_io.size(path).await
+            }
+        }
+    }
+
+    /// Whether any file has been checked individually
+    fn headed(&self) -> bool {
+        self.headed.load(Ordering::Relaxed)
+    }
+
+    /// Whether any file has been checked by listing its directory
+    fn listed(&self) -> bool {
+        self.listed.load(Ordering::Relaxed)
+    }
+
+    /// Counts a check of the path's directory, returning the listing checker once it's
+    /// been checked often enough to be worth listing.
+    fn directory_checker(&self, path: &str) -> Option<&DirectoryListingExistenceChecker> {
+        let directories = self.directories.as_ref()?;
+        let (bucket, key) = parse_s3_url(path)?;
+        let (directory, _) = split_directory(key);
+        let mut checks_per_directory = self.checks_per_directory.lock().unwrap();
+        let checks =
+            checks_per_directory.get_or_insert_with(format!("{}/{}", bucket, directory), || 0);
+        *checks += 1;
+        (*checks > HEAD_REQUESTS_PER_DIRECTORY).then_some(directories)
     }
 }
 
+/// Directories whose listings (or, when falling back, check counts) are kept
+const CACHED_DIRECTORIES: usize = 1024;
+
+/// Sizes of the objects in a directory, keyed by name
+type Listing = Arc<HashMap<Box<str>, u64>>;
+
+/// Checks file existence by listing each file's directory (usually a partition) on first use.
+///
+/// Only the most recently listed directories are kept, so memory is bounded by
+/// partition size rather than table size. Manifests tend to group files by
+/// partition, so most checks hit a cached listing; when they interleave more than
+/// `CACHED_DIRECTORIES` partitions, directories are listed again after eviction.
+/// Files not on S3 are checked through FileIO.
+pub struct DirectoryListingExistenceChecker {
+    client: Client,
+    file_io: FileIOExistenceChecker,
+    cache: Mutex<DirectoryCache<Listing>>,
+}
+
+impl DirectoryListingExistenceChecker {
+    fn new(client: Client, file_io: FileIO) -> Self {
+        Self {
+            client,
+            file_io: FileIOExistenceChecker::new(file_io),
+            cache: Mutex::new(DirectoryCache::new(CACHED_DIRECTORIES)),
+        }
+    }
+
+    /// Returns the sizes of the objects directly under `directory`, listing it if not cached.
+    async fn listing(&self, bucket: &str, directory: &str) -> Result<Listing> {
+        let cache_key = format!("{}/{}", bucket, directory);
+        if let Some(listing) = self.cache.lock().unwrap().get(&cache_key) {
+            return Ok(Arc::clone(listing));
+        }
+
+        // Concurrent checks in the same directory may both list it, which is harmless
+        debug!(bucket = %bucket, directory = %directory, "Listing S3 directory");
+        let mut sizes = HashMap::new();
+        for_each_s3_object(&self.client, bucket, directory, Some("/"), |object| {
+            if let Some(key) = object.key() {
+                let name = key.strip_prefix(directory).unwrap_or(key);
+                sizes.insert(name.into(), object_size(object));
+            }
+        })
+        .await?;
+        let listing = Arc::new(sizes);
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(cache_key, Arc::clone(&listing));
+        Ok(listing)
+    }
+}
+
+#[async_trait]
+impl FileExistenceChecker for DirectoryListingExistenceChecker {
+    async fn exists(&self, path: &str) -> Result<bool> {
+        Ok(self.size(path).await?.is_some())
+    }
+
+    async fn size(&self, path: &str) -> Result<Option<u64>> {
+        let Some((bucket, key)) = parse_s3_url(path) else {
+            return self.file_io.size(path).await;
+        };
+        let (directory, name) = split_directory(key);
+        Ok(self.listing(bucket, directory).await?.get(name).copied())
+    }
+
+    fn strategy(&self) -> &'static str {
+        "s3-directory-listing"
+    }
+}
+
+/// Splits an object key after its last `/`, into its directory (with the slash) and name.
+fn split_directory(key: &str) -> (&str, &str) {
+    match key.rfind('/') {
+        Some(pos) => key.split_at(pos + 1),
+        None => ("", key),
+    }
+}
+
+/// Something kept per directory (e.g. its listing), evicting the oldest once full.
+struct DirectoryCache<V> {
+    capacity: usize,
+    values: HashMap<String, V>,
+    order: VecDeque<String>,
+}
+
+impl<V> DirectoryCache<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            values: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn get(&self, directory: &str) -> Option<&V> {
+        self.values.get(directory)
+    }
+
+    /// Keeps the existing value if the directory is already cached
+    fn insert(&mut self, directory: String, value: V) {
+        self.get_or_insert_with(directory, || value);
+    }
+
+    fn get_or_insert_with(&mut self, directory: String, value: impl FnOnce() -> V) -> &mut V {
+        if !self.values.contains_key(&directory) {
+            if self.order.len() >= self.capacity
+                && let Some(oldest) = self.order.pop_front()
+            {
+                self.values.remove(&oldest);
+            }
+            self.order.push_back(directory.clone());
+        }
+        self.values.entry(directory).or_insert_with(value)
+    }
+}
+
+/// Above this many files, listing the whole data prefix up front would hold
+/// too many keys in memory, so directories are listed as they're needed instead
+pub const PRELOAD_FILE_LIMIT: u64 = 1_000_000;
+
 /// Creates a file existence checker, using S3 prefix listing if possible.
 ///
 /// If the data prefix is on S3 and an S3 client is provided, uses a bulk
-/// `ListObjectsV2` call and returns a `PreloadedExistenceChecker`.
+/// `ListObjectsV2` call and returns a `PreloadedExistenceChecker`. When more than
+/// `PRELOAD_FILE_LIMIT` files are expected, returns a `DirectoryListingExistenceChecker`
+/// instead, to bound memory use.
 ///
 /// Otherwise, returns a `FileIOExistenceChecker` that delegates to per-file checks.
 pub async fn create_existence_checker(
     file_io: FileIO,
     data_prefix: &str,
     s3_client: Option<&Client>,
+    expected_files: u64,
 ) -> Result<Box<dyn FileExistenceChecker>> {
-    debug!(data_prefix = %data_prefix, "Creating file existence checker");
+    debug!(data_prefix = %data_prefix, expected_files, "Creating file existence checker");
 
     // Try S3 optimization: parse URL and use provided client
     if let Some((bucket, prefix)) = parse_s3_url(data_prefix)
         && let Some(client) = s3_client
     {
+        if expected_files > PRELOAD_FILE_LIMIT {
+            debug!("Using S3 directory listing existence checker");
+            return Ok(Box::new(DirectoryListingExistenceChecker::new(
+                client.clone(),
+                file_io,
+            )));
+        }
+
         let base_url = format!("s3://{}/{}", bucket, prefix);
-        let suffixes = list_object_suffixes(client, bucket, prefix).await?;
+        let sizes = list_object_sizes(client, bucket, prefix).await?;
         debug!(
-            file_count = suffixes.len(),
+            file_count = sizes.len(),
             "Using preloaded S3 existence checker"
         );
-        return Ok(Box::new(PreloadedExistenceChecker::new(base_url, suffixes)));
+        let fallback = FallbackExistenceChecker::new(file_io, Some(client));
+        return Ok(Box::new(PreloadedExistenceChecker::new(
+            base_url, sizes, fallback,
+        )));
     }
 
     debug!("Using FileIO existence checker");
@@ -101,41 +370,211 @@ fn parse_s3_url(url: &str) -> Option<(&str, &str)> {
     Some((bucket, key))
 }
 
-/// Lists all objects in an S3 bucket with the given prefix, returning suffixes only.
+/// Lists all objects in an S3 bucket with the given prefix, returning suffixes and sizes.
 ///
-/// Returns the portion of each object key after the prefix, to save memory.
-async fn list_object_suffixes(
+/// Keys each size by the portion of the object key after the prefix, to save memory.
+async fn list_object_sizes(
     client: &Client,
     bucket: &str,
     prefix: &str,
-) -> Result<HashSet<Box<str>>> {
+) -> Result<HashMap<Box<str>, u64>> {
     info!(bucket = %bucket, prefix = %prefix, "Listing S3 objects");
-    let mut suffixes = HashSet::new();
+    let mut sizes = HashMap::new();
 
+    for_each_s3_object(client, bucket, prefix, None, |object| {
+        if let Some(key) = object.key() {
+            // Strip the prefix to get just the suffix
+            let suffix = key.strip_prefix(prefix).unwrap_or(key);
+            sizes.insert(suffix.into(), object_size(object));
+        }
+    })
+    .await?;
+
+    Ok(sizes)
+}
+
+fn object_size(object: &Object) -> u64 {
+    object.size().unwrap_or_default().max(0) as u64
+}
+
+/// Pages through every object in an S3 bucket with the given prefix.
+///
+/// With a delimiter, only objects directly under the prefix are visited.
+async fn for_each_s3_object(
+    client: &Client,
+    bucket: &str,
+    prefix: &str,
+    delimiter: Option<&str>,
+    mut f: impl FnMut(&Object),
+) -> Result<()> {
     let mut paginator = client
         .list_objects_v2()
         .bucket(bucket)
         .prefix(prefix)
+        .set_delimiter(delimiter.map(String::from))
         .into_paginator()
         .send();
 
     while let Some(result) = paginator.next().await {
         let page = result.context("Failed to list S3 objects")?;
-        for object in page.contents() {
+        page.contents().iter().for_each(&mut f);
+    }
+
+    Ok(())
+}
+
+/// An object found by listing a location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListedObject {
+    pub path: String,
+    pub size: u64,
+    pub last_modified_ms: Option<i64>,
+}
+
+/// Lists every object under a location.
+///
+/// Uses a bulk S3 listing for S3 locations (which requires an S3 client), and
+/// a filesystem walk for local paths. Other storage isn't supported.
+pub async fn list_objects(location: &str, s3_client: Option<&Client>) -> Result<Vec<ListedObject>> {
+    let location = location.trim_end_matches('/');
+
+    if let Some((bucket, key)) = parse_s3_url(location) {
+        let client =
+            s3_client.with_context(|| format!("Listing {} requires S3 credentials", location))?;
+        let prefix = format!("{}/", key);
+        info!(bucket = %bucket, prefix = %prefix, "Listing S3 objects");
+        let mut objects = Vec::new();
+        for_each_s3_object(client, bucket, &prefix, None, |object| {
             if let Some(key) = object.key() {
-                // Strip the prefix to get just the suffix
-                let suffix = key.strip_prefix(prefix).unwrap_or(key);
-                suffixes.insert(suffix.into());
+                objects.push(ListedObject {
+                    path: format!("s3://{}/{}", bucket, key),
+                    size: object_size(object),
+                    last_modified_ms: object
+                        .last_modified()
+                        .and_then(|last_modified| last_modified.to_millis().ok()),
+                });
             }
-        }
+        })
+        .await?;
+        return Ok(objects);
     }
 
-    Ok(suffixes)
+    let root = match location.strip_prefix("file://") {
+        Some(path) => path,
+        None if location.starts_with('/') => location,
+        None => anyhow::bail!(
+            "Listing is only supported for S3 and local locations, not {}",
+            location
+        ),
+    };
+    let (root, location) = (PathBuf::from(root), location.to_string());
+    tokio::task::spawn_blocking(move || {
+        let mut objects = Vec::new();
+        walk_dir(&root, &location, &mut objects)?;
+        Ok(objects)
+    })
+    .await?
+}
+
+/// Recursively lists the files under `dir`, naming them relative to `location`.
+fn walk_dir(dir: &Path, location: &str, objects: &mut Vec<ListedObject>) -> Result<()> {
+    let entries =
+        std::fs::read_dir(dir).with_context(|| format!("Failed to list {}", dir.display()))?;
+    for entry in entries {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let name = entry.file_name();
+        let location = format!("{}/{}", location, name.to_string_lossy());
+        if metadata.is_dir() {
+            walk_dir(&entry.path(), &location, objects)?;
+        } else {
+            objects.push(ListedObject {
+                path: location,
+                size: metadata.len(),
+                last_modified_ms: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .and_then(|duration| i64::try_from(duration.as_millis()).ok()),
+            });
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aws_sdk_s3::config::http::{HttpRequest, HttpResponse};
+    use aws_sdk_s3::config::{BehaviorVersion, Credentials, Region};
+    use aws_sdk_s3::primitives::SdkBody;
+    use aws_smithy_runtime_api::client::http::{
+        HttpConnector, HttpConnectorFuture, SharedHttpConnector, http_client_fn,
+    };
+    use std::sync::atomic::AtomicUsize;
+
+    /// Answers `ListObjectsV2` requests from a fixed set of objects, counting them
+    #[derive(Debug)]
+    struct FakeS3 {
+        objects: Vec<(String, u64)>,
+        listings: Arc<AtomicUsize>,
+    }
+
+    impl HttpConnector for FakeS3 {
+        fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+            self.listings.fetch_add(1, Ordering::SeqCst);
+            let url = url::Url::parse(request.uri()).unwrap();
+            let param = |name: &str| {
+                url.query_pairs()
+                    .find(|(key, _)| key == name)
+                    .map(|(_, value)| value.into_owned())
+                    .unwrap_or_default()
+            };
+            let (prefix, delimiter) = (param("prefix"), param("delimiter"));
+            let contents: String = self
+                .objects
+                .iter()
+                .filter(|(key, _)| {
+                    key.strip_prefix(&prefix)
+                        .is_some_and(|rest| delimiter.is_empty() || !rest.contains(&delimiter))
+                })
+                .map(|(key, size)| {
+                    format!(
+                        "<Contents><Key>{}</Key><Size>{}</Size></Contents>",
+                        key, size
+                    )
+                })
+                .collect();
+            let body = format!(
+                "<ListBucketResult><Name>bucket</Name><Prefix>{}</Prefix>\
+                 <IsTruncated>false</IsTruncated>{}</ListBucketResult>",
+                prefix, contents
+            );
+            HttpConnectorFuture::ready(Ok(HttpResponse::new(
+                200.try_into().unwrap(),
+                SdkBody::from(body),
+            )))
+        }
+    }
+
+    /// Returns a client for a bucket holding the given objects, and a count of its listings
+    fn fake_s3_client(objects: &[(&str, u64)]) -> (Client, Arc<AtomicUsize>) {
+        let listings = Arc::new(AtomicUsize::new(0));
+        let connector = SharedHttpConnector::new(FakeS3 {
+            objects: objects
+                .iter()
+                .map(|(key, size)| (key.to_string(), *size))
+                .collect(),
+            listings: Arc::clone(&listings),
+        });
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .credentials_provider(Credentials::new("key", "secret", None, None, "test"))
+            .http_client(http_client_fn(move |_, _| connector.clone()))
+            .build();
+        (Client::from_conf(config), listings)
+    }
 
     #[test]
     fn test_parse_s3_url() {
@@ -151,13 +590,24 @@ mod tests {
         assert_eq!(parse_s3_url("not-a-url"), None);
     }
 
+    fn preloaded_checker(
+        sizes: HashMap<Box<str>, u64>,
+        file_io: FileIO,
+    ) -> PreloadedExistenceChecker {
+        PreloadedExistenceChecker::new(
+            "s3://bucket/data/".to_string(),
+            sizes,
+            FallbackExistenceChecker::new(file_io, None),
+        )
+    }
+
     #[tokio::test]
     async fn test_preloaded_checker() {
-        let mut suffixes = HashSet::new();
-        suffixes.insert("file1.parquet".into());
-        suffixes.insert("file2.parquet".into());
+        let mut sizes = HashMap::new();
+        sizes.insert("file1.parquet".into(), 100);
+        sizes.insert("file2.parquet".into(), 200);
 
-        let checker = PreloadedExistenceChecker::new("s3://bucket/data/".to_string(), suffixes);
+        let checker = preloaded_checker(sizes, FileIO::new_with_memory());
 
         assert!(
             checker
@@ -179,12 +629,182 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_preloaded_checker_size() {
+        let mut sizes = HashMap::new();
+        sizes.insert("file1.parquet".into(), 100);
+
+        let checker = preloaded_checker(sizes, FileIO::new_with_memory());
+
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/file1.parquet")
+                .await
+                .unwrap(),
+            Some(100)
+        );
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/file2.parquet")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_io_checker_size() {
+        let file_io = FileIO::new_with_memory();
+        file_io
+            .new_output("memory://data/file1.parquet")
+            .unwrap()
+            .write(bytes::Bytes::from_static(b"12345"))
+            .await
+            .unwrap();
+
+        let checker = FileIOExistenceChecker::new(file_io);
+
+        assert_eq!(
+            checker.size("memory://data/file1.parquet").await.unwrap(),
+            Some(5)
+        );
+        assert_eq!(
+            checker.size("memory://data/file2.parquet").await.unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_split_directory() {
+        assert_eq!(
+            split_directory("table/data/day=2026-10-01/a.parquet"),
+            ("table/data/day=2026-10-01/", "a.parquet")
+        );
+        assert_eq!(split_directory("a.parquet"), ("", "a.parquet"));
+    }
+
+    #[test]
+    fn test_directory_cache_evicts_oldest() {
+        let mut cache = DirectoryCache::new(2);
+        for directory in ["a/", "b/", "a/", "c/"] {
+            *cache.get_or_insert_with(directory.to_string(), || 0) += 1;
+        }
+
+        assert_eq!(cache.get("a/"), None);
+        assert_eq!(cache.get("b/"), Some(&1));
+        assert_eq!(cache.get("c/"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_directory_listing_checker() {
+        let (client, listings) = fake_s3_client(&[
+            ("data/day=1/a.parquet", 100),
+            ("data/day=1/b.parquet", 200),
+            ("data/day=1/nested/c.parquet", 300),
+            ("data/day=2/d.parquet", 400),
+        ]);
+        let checker = DirectoryListingExistenceChecker::new(client, FileIO::new_with_memory());
+
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/day=1/a.parquet")
+                .await
+                .unwrap(),
+            Some(100)
+        );
+        assert!(
+            checker
+                .exists("s3://bucket/data/day=1/b.parquet")
+                .await
+                .unwrap()
+        );
+        assert!(
+            !checker
+                .exists("s3://bucket/data/day=1/missing.parquet")
+                .await
+                .unwrap()
+        );
+        // Checks in a directory that's already listed hit the cache
+        assert_eq!(listings.load(Ordering::SeqCst), 1);
+
+        // A directory that hasn't been listed yet is listed on first use
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/day=2/d.parquet")
+                .await
+                .unwrap(),
+            Some(400)
+        );
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/day=1/nested/c.parquet")
+                .await
+                .unwrap(),
+            Some(300)
+        );
+        assert_eq!(listings.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_preloaded_checker_lists_busy_directories_outside_prefix() {
+        let (client, listings) = fake_s3_client(&[("elsewhere/file1.parquet", 100)]);
+        let checker = PreloadedExistenceChecker::new(
+            "s3://bucket/data/".to_string(),
+            HashMap::new(),
+            FallbackExistenceChecker::new(FileIO::new_with_memory(), Some(&client)),
+        );
+        assert_eq!(checker.strategy(), "s3-listing");
+
+        // The in-memory FileIO doesn't have the file, but the S3 listing does
+        for _ in 0..HEAD_REQUESTS_PER_DIRECTORY {
+            assert!(
+                !checker
+                    .exists("s3://bucket/elsewhere/file1.parquet")
+                    .await
+                    .unwrap()
+            );
+        }
+        assert_eq!(checker.strategy(), "s3-listing+head");
+        assert_eq!(listings.load(Ordering::SeqCst), 0);
+
+        assert_eq!(
+            checker
+                .size("s3://bucket/elsewhere/file1.parquet")
+                .await
+                .unwrap(),
+            Some(100)
+        );
+        assert_eq!(checker.strategy(), "s3-listing+head+directory-listing");
+        assert_eq!(listings.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_directory_listing_checker_outside_s3() {
+        let file_io = FileIO::new_with_memory();
+        file_io
+            .new_output("memory://data/file1.parquet")
+            .unwrap()
+            .write(bytes::Bytes::from_static(b"12345"))
+            .await
+            .unwrap();
+        let (client, listings) = fake_s3_client(&[]);
+
+        let checker = DirectoryListingExistenceChecker::new(client, file_io);
+
+        assert_eq!(
+            checker.size("memory://data/file1.parquet").await.unwrap(),
+            Some(5)
+        );
+        assert!(!checker.exists("memory://data/file2.parquet").await.unwrap());
+        assert_eq!(listings.load(Ordering::SeqCst), 0);
+    }
+
     #[tokio::test]
     async fn test_preloaded_checker_with_non_matching_prefix() {
-        let mut suffixes = HashSet::new();
-        suffixes.insert("file1.parquet".into());
+        let mut sizes = HashMap::new();
+        sizes.insert("file1.parquet".into(), 100);
 
-        let checker = PreloadedExistenceChecker::new("s3://bucket/data/".to_string(), suffixes);
+        let checker = preloaded_checker(sizes, FileIO::new_with_memory());
 
         // Path with different prefix should return false, not panic
         assert!(
@@ -194,4 +814,33 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn test_preloaded_checker_falls_back_outside_prefix() {
+        let file_io = FileIO::new_with_memory();
+        file_io
+            .new_output("s3://bucket/elsewhere/file2.parquet")
+            .unwrap()
+            .write(bytes::Bytes::from_static(b"123"))
+            .await
+            .unwrap();
+
+        let checker = preloaded_checker(HashMap::new(), file_io);
+
+        assert_eq!(
+            checker
+                .size("s3://bucket/elsewhere/file2.parquet")
+                .await
+                .unwrap(),
+            Some(3)
+        );
+        // Files under the prefix are only looked up in the listing
+        assert_eq!(
+            checker
+                .size("s3://bucket/data/file2.parquet")
+                .await
+                .unwrap(),
+            None
+        );
+    }
 }
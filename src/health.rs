@@ -0,0 +1,475 @@
+//! Table health report: file sizes, manifest and delete file overhead, and
+//! metadata growth, with recommendations for maintenance.
+//!
+//! Walks the current snapshot's manifests via `iterate_files`, so the cost is
+//! one manifest list plus every manifest, but no data files.
+
+use crate::cli::ContentFilter;
+use crate::table_commands::{FileType, iterate_files};
+use crate::terminal_output::TerminalOutput;
+use anyhow::Result;
+use futures::TryStreamExt;
+use iceberg::spec::{ManifestContentType, ManifestFile};
+use iceberg::table::Table;
+use serde::Serialize;
+use std::io::Write;
+
+const MIB: u64 = 1024 * 1024;
+
+/// Iceberg's default for `write.target-file-size-bytes`
+const DEFAULT_TARGET_FILE_SIZE: u64 = 512 * MIB;
+
+/// Upper bounds of the file size histogram buckets; the last bucket is unbounded
+const HISTOGRAM_BOUNDS: [u64; 5] = [MIB, 8 * MIB, 32 * MIB, 128 * MIB, 512 * MIB];
+
+/// Manifests smaller than this are worth merging once there are many of them
+const SMALL_MANIFEST_SIZE: u64 = MIB;
+const MANY_MANIFESTS: u64 = 100;
+const MANY_SNAPSHOTS: usize = 100;
+const LONG_METADATA_LOG: usize = 100;
+/// Delete files per data file above which reads pay noticeably for merge-on-read
+const HIGH_DELETE_RATIO: f64 = 0.1;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct HealthReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snapshot_id: Option<i64>,
+    snapshot_count: usize,
+    metadata_log_length: usize,
+    manifests: ManifestStats,
+    data_files: DataFileStats,
+    delete_files: DeleteFileStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delete_to_data_file_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delete_to_data_record_ratio: Option<f64>,
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ManifestStats {
+    count: u64,
+    data_manifests: u64,
+    delete_manifests: u64,
+    total_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    average_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DataFileStats {
+    count: u64,
+    records: u64,
+    total_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    average_bytes: Option<u64>,
+    target_file_size_bytes: u64,
+    under_target_size: u64,
+    size_histogram: Vec<HistogramBucket>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct HistogramBucket {
+    min_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_bytes: Option<u64>,
+    files: u64,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DeleteFileStats {
+    position_delete_files: u64,
+    equality_delete_files: u64,
+    deletion_vectors: u64,
+    records: u64,
+    total_bytes: u64,
+}
+
+impl ManifestStats {
+    fn add(&mut self, manifest_file: &ManifestFile) {
+        self.count += 1;
+        self.total_bytes += manifest_file.manifest_length.max(0) as u64;
+        match manifest_file.content {
+            ManifestContentType::Data => self.data_manifests += 1,
+            ManifestContentType::Deletes => self.delete_manifests += 1,
+        }
+    }
+}
+
+impl DeleteFileStats {
+    fn count(&self) -> u64 {
+        self.position_delete_files + self.equality_delete_files + self.deletion_vectors
+    }
+}
+
+impl DataFileStats {
+    fn new(target_file_size_bytes: u64) -> Self {
+        let mut min_bytes = 0;
+        let size_histogram = HISTOGRAM_BOUNDS
+            .iter()
+            .map(Some)
+            .chain(std::iter::once(None))
+            .map(|max_bytes| {
+                let bucket = HistogramBucket {
+                    min_bytes,
+                    max_bytes: max_bytes.copied(),
+                    files: 0,
+                };
+                min_bytes = max_bytes.copied().unwrap_or_default();
+                bucket
+            })
+            .collect();
+
+        Self {
+            count: 0,
+            records: 0,
+            total_bytes: 0,
+            average_bytes: None,
+            target_file_size_bytes,
+            under_target_size: 0,
+            size_histogram,
+        }
+    }
+
+    fn add(&mut self, record_count: u64, file_size_in_bytes: u64) {
+        self.count += 1;
+        self.records += record_count;
+        self.total_bytes += file_size_in_bytes;
+        if file_size_in_bytes < self.target_file_size_bytes {
+            self.under_target_size += 1;
+        }
+        if let Some(bucket) = self
+            .size_histogram
+            .iter_mut()
+            .find(|bucket| bucket.max_bytes.is_none_or(|max| file_size_in_bytes < max))
+        {
+            bucket.files += 1;
+        }
+    }
+}
+
+/// Reports on the health of the table's current snapshot.
+///
+/// The target file size defaults to the table's `write.target-file-size-bytes`
+/// property, or Iceberg's default if that isn't set.
+pub async fn handle_health<W: Write>(
+    table: &Table,
+    target_file_size: Option<u64>,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let metadata = table.metadata();
+    let target_file_size_bytes = target_file_size
+        .or_else(|| {
+            metadata
+                .properties()
+                .get("write.target-file-size-bytes")
+                .and_then(|value| value.parse().ok())
+        })
+        .unwrap_or(DEFAULT_TARGET_FILE_SIZE);
+
+    let mut manifests = ManifestStats::default();
+    let mut data_files = DataFileStats::new(target_file_size_bytes);
+    let mut delete_files = DeleteFileStats::default();
+
+    if let Some(snapshot) = metadata.current_snapshot() {
+        let mut files = Box::pin(iterate_files(
+            table,
+            snapshot,
+            ContentFilter::All,
+            None,
+            false,
+        ));
+        while let Some(record) = files.try_next().await? {
+            if let Some(manifest_file) = &record.manifest_file {
+                manifests.add(manifest_file);
+                continue;
+            }
+            let Some(entry) = record.entry else {
+                continue;
+            };
+            let data_file = entry.data_file();
+            match FileType::of(data_file) {
+                FileType::Data => {
+                    data_files.add(data_file.record_count(), data_file.file_size_in_bytes());
+                    continue;
+                }
+                FileType::PositionDelete => delete_files.position_delete_files += 1,
+                FileType::EqualityDelete => delete_files.equality_delete_files += 1,
+                FileType::DeletionVector => delete_files.deletion_vectors += 1,
+                FileType::Metadata | FileType::ManifestList | FileType::Manifest => continue,
+            }
+            delete_files.records += data_file.record_count();
+            // Several deletion vectors can share a Puffin file, so count only each one's blob
+            delete_files.total_bytes += data_file
+                .content_size_in_bytes()
+                .map(|size| size.max(0) as u64)
+                .unwrap_or_else(|| data_file.file_size_in_bytes());
+        }
+    }
+
+    manifests.average_bytes = average(manifests.total_bytes, manifests.count);
+    data_files.average_bytes = average(data_files.total_bytes, data_files.count);
+    let delete_to_data_file_ratio = ratio(delete_files.count(), data_files.count);
+    let delete_to_data_record_ratio = ratio(delete_files.records, data_files.records);
+
+    let mut report = HealthReport {
+        snapshot_id: metadata.current_snapshot_id(),
+        snapshot_count: metadata.snapshots().len(),
+        metadata_log_length: metadata.metadata_log().len(),
+        manifests,
+        data_files,
+        delete_files,
+        delete_to_data_file_ratio,
+        delete_to_data_record_ratio,
+        warnings: Vec::new(),
+    };
+    report.warnings = warnings(&report);
+
+    output.display_object(&report)
+}
+
+fn average(total: u64, count: u64) -> Option<u64> {
+    (count > 0).then(|| total / count)
+}
+
+fn ratio(numerator: u64, denominator: u64) -> Option<f64> {
+    (denominator > 0).then(|| numerator as f64 / denominator as f64)
+}
+
+fn warnings(report: &HealthReport) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let data_files = &report.data_files;
+    // A single small file has nothing to be compacted with
+    if data_files.under_target_size > 1 && data_files.under_target_size * 2 > data_files.count {
+        warnings.push(format!(
+            "{} of {} data files are smaller than the target size of {} bytes; compaction recommended",
+            data_files.under_target_size, data_files.count, data_files.target_file_size_bytes
+        ));
+    }
+
+    let manifests = &report.manifests;
+    if manifests.count >= MANY_MANIFESTS
+        && manifests
+            .average_bytes
+            .is_some_and(|average| average < SMALL_MANIFEST_SIZE)
+    {
+        warnings.push(format!(
+            "{} manifests averaging {} bytes; rewriting manifests recommended",
+            manifests.count,
+            manifests.average_bytes.unwrap_or_default()
+        ));
+    }
+
+    if let Some(ratio) = report.delete_to_data_file_ratio
+        && ratio > HIGH_DELETE_RATIO
+    {
+        warnings.push(format!(
+            "{} delete files for {} data files; compaction recommended to apply deletes",
+            report.delete_files.count(),
+            data_files.count
+        ));
+    }
+
+    if report.snapshot_count >= MANY_SNAPSHOTS {
+        warnings.push(format!(
+            "{} snapshots retained; expiring snapshots recommended",
+            report.snapshot_count
+        ));
+    }
+
+    if report.metadata_log_length >= LONG_METADATA_LOG {
+        warnings.push(format!(
+            "metadata log has {} entries; consider setting write.metadata.delete-after-commit.enabled",
+            report.metadata_log_length
+        ));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TableCommands;
+    use crate::table_commands::{handle_table_command, load_table};
+    use crate::test_support::*;
+
+    async fn health(table: &Table, target_file_size: Option<u64>) -> Result<serde_json::Value> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            table,
+            TableCommands::Health { target_file_size },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        Ok(serde_json::from_slice(&buffer)?)
+    }
+
+    #[tokio::test]
+    async fn test_health() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_snapshot(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let data_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![
+                data_file("s3://bucket/table/data/a.parquet", 10, 1000),
+                data_file("s3://bucket/table/data/b.parquet", 20, 2 * MIB),
+                data_file("s3://bucket/table/data/c.parquet", 30, 600 * MIB),
+            ],
+        )
+        .await;
+        let delete_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            vec![position_delete_file(
+                "s3://bucket/table/data/d.parquet",
+                3,
+                300,
+            )],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![data_manifest, delete_manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let report = health(&table, None).await?;
+
+        assert_eq!(report["snapshot-id"], 123);
+        assert_eq!(report["snapshot-count"], 1);
+        assert_eq!(report["metadata-log-length"], 0);
+        assert_eq!(report["manifests"]["count"], 2);
+        assert_eq!(report["manifests"]["data-manifests"], 1);
+        assert_eq!(report["manifests"]["delete-manifests"], 1);
+
+        let data_files = &report["data-files"];
+        assert_eq!(data_files["count"], 3);
+        assert_eq!(data_files["records"], 60);
+        assert_eq!(data_files["target-file-size-bytes"], 512 * MIB);
+        assert_eq!(data_files["under-target-size"], 2);
+        let histogram: Vec<u64> = data_files["size-histogram"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|bucket| bucket["files"].as_u64().unwrap())
+            .collect();
+        assert_eq!(histogram, vec![1, 1, 0, 0, 0, 1]);
+        assert!(data_files["size-histogram"][5].get("max-bytes").is_none());
+
+        assert_eq!(report["delete-files"]["position-delete-files"], 1);
+        assert_eq!(report["delete-files"]["records"], 3);
+        assert_eq!(report["delete-to-data-record-ratio"], 0.05);
+
+        let warnings = report["warnings"].as_array().unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(
+            warnings[0]
+                .as_str()
+                .unwrap()
+                .starts_with("2 of 3 data files are smaller than the target size")
+        );
+        assert!(
+            warnings[1]
+                .as_str()
+                .unwrap()
+                .starts_with("1 delete files for 3 data files")
+        );
+
+        // A smaller target leaves only one small file, which isn't worth compacting
+        let report = health(&table, Some(MIB)).await?;
+        assert_eq!(report["data-files"]["under-target-size"], 1);
+        assert_eq!(report["warnings"].as_array().unwrap().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_health_deletion_vectors_sharing_a_puffin_file() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_snapshot(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let puffin = "s3://bucket/table/data/dv.puffin";
+        let data_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![data_file("s3://bucket/table/data/a.parquet", 10, 1000)],
+        )
+        .await;
+        let delete_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            vec![
+                deletion_vector(puffin, "s3://bucket/table/data/a.parquet", 4, 40),
+                deletion_vector(puffin, "s3://bucket/table/data/b.parquet", 44, 60),
+            ],
+        )
+        .await;
+        let manifest_bytes =
+            (data_manifest.manifest_length + delete_manifest.manifest_length) as u64;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![data_manifest, delete_manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let report = health(&table, None).await?;
+
+        assert_eq!(report["manifests"]["count"], 2);
+        assert_eq!(report["manifests"]["total-bytes"], manifest_bytes);
+        assert_eq!(report["delete-files"]["deletion-vectors"], 2);
+        assert_eq!(report["delete-files"]["total-bytes"], 100);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_health_empty_table() -> Result<()> {
+        let metadata_json = serde_json::to_string(&empty_metadata())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let report = health(&table, None).await?;
+
+        assert!(report.get("snapshot-id").is_none());
+        assert_eq!(report["data-files"]["count"], 0);
+        assert!(report.get("delete-to-data-file-ratio").is_none());
+        assert_eq!(report["warnings"], serde_json::json!([]));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,23 @@
 pub mod aws;
 pub mod catalog_commands;
 pub mod cli;
+pub mod columnar_format;
+pub mod deep_verify;
+pub mod delimited_format;
 pub mod error;
 pub mod file_existence;
+pub mod health;
+pub mod orphans;
+pub mod partitions;
+pub mod record_filter;
 pub mod rest;
+pub mod snapshot_diff;
 pub mod table_commands;
+pub mod table_format;
 pub mod terminal_output;
+pub mod values;
+pub mod verify;
+pub mod verify_report;
+
+#[cfg(test)]
+mod test_support;
@@ -1,13 +1,16 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bergr::aws::{get_aws_config, glue_catalog, s3_file_io};
 use bergr::catalog_commands::handle_catalog_command;
-use bergr::cli::{Cli, Commands};
+use bergr::cli::{Cli, Commands, OutputFormat};
 use bergr::error::ExpectedError;
+use bergr::record_filter::RecordFilter;
 use bergr::rest::rest_catalog;
 use bergr::table_commands::{handle_table_command, load_table};
 use bergr::terminal_output::TerminalOutput;
 use clap::Parser;
 use iceberg::io::FileIO;
+use std::fs::File;
+use std::io::{BufWriter, IsTerminal, Write};
 use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -30,12 +33,13 @@ async fn main() {
             .init();
     }
 
-    if let Err(err) = run(cli.command).await {
+    let debug = cli.debug;
+    if let Err(err) = run(cli).await {
         // Check if this is a wrapped ExpectedError (expected user-facing error)
         if let Some(expected_error) = err.downcast_ref::<ExpectedError>() {
             eprintln!("ERROR: {expected_error}");
             std::process::exit(1);
-        } else if cli.debug {
+        } else if debug {
             // Debug mode: show full error chain
             eprintln!("ERROR: {err:?}");
             std::process::exit(2);
@@ -48,8 +52,30 @@ async fn main() {
     }
 }
 
-async fn run(command: Commands) -> Result<()> {
-    match command {
+async fn run(cli: Cli) -> Result<()> {
+    let binary = matches!(cli.format, OutputFormat::Parquet | OutputFormat::Arrow);
+    let writer: Box<dyn Write> = match &cli.output {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("Failed to create output file {}", path.display())
+            })?))
+        }
+        None if binary && std::io::stdout().is_terminal() => {
+            return Err(ExpectedError::UserInput(
+                "Parquet and Arrow output must be written with --output <path> or piped"
+                    .to_string(),
+            )
+            .into());
+        }
+        None => Box::new(std::io::stdout()),
+    };
+    let mut output = TerminalOutput::with_writer(writer)
+        .with_format(cli.format)
+        .with_filter(RecordFilter::new(cli.fields, cli.conditions))
+        .with_sample_rows(cli.sample_rows);
+    let output = &mut output;
+
+    match cli.command {
         Commands::From { location, command } => {
             let is_s3 = location.starts_with("s3://") || location.starts_with("s3a://");
             let (file_io, s3) = if is_s3 {
@@ -62,15 +88,13 @@ async fn run(command: Commands) -> Result<()> {
                 (FileIO::new_with_fs(), None)
             };
             let table = load_table(&file_io, &location).await?;
-            let mut output = TerminalOutput::new();
-            handle_table_command(&table, command, &mut output, s3.as_ref()).await?;
+            handle_table_command(&table, command, output, s3.as_ref()).await?;
         }
         Commands::Glue { command } => {
             let aws_config = get_aws_config().await;
             let catalog = glue_catalog(&aws_config).await?;
             let s3 = aws_sdk_s3::Client::new(&aws_config);
-            let mut output = TerminalOutput::new();
-            handle_catalog_command(&catalog, command, &mut output, Some(&s3)).await?;
+            handle_catalog_command(&catalog, command, output, Some(&s3)).await?;
         }
         Commands::Rest {
             uri,
@@ -78,10 +102,9 @@ async fn run(command: Commands) -> Result<()> {
             command,
         } => {
             let catalog = rest_catalog(&uri, warehouse.as_deref()).await?;
-            let mut output = TerminalOutput::new();
-            handle_catalog_command(&catalog, command, &mut output, None).await?;
+            handle_catalog_command(&catalog, command, output, None).await?;
         }
     }
 
-    Ok(())
+    output.flush()
 }
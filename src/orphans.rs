@@ -0,0 +1,329 @@
+//! Finds files under a table's location that no retained snapshot or
+//! metadata file references.
+//!
+//! The reverse of `files --verify`: lists the table location and subtracts
+//! everything reachable from the table metadata. Only reports; never deletes.
+
+use crate::file_existence::{ListedObject, list_objects};
+use crate::table_commands::load_manifest_list;
+use crate::terminal_output::TerminalOutput;
+use anyhow::{Context, Result};
+use futures::{StreamExt, stream};
+use iceberg::spec::{ManifestFile, ManifestStatus};
+use iceberg::table::Table;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+/// One line of `orphans` output: an unreferenced file, or the final summary.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum OrphanRecord {
+    Orphan(OrphanFile),
+    Summary(OrphanSummary),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct OrphanFile {
+    path: String,
+    size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified_ms: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct OrphanSummary {
+    listed_files: usize,
+    referenced_files: usize,
+    orphan_files: usize,
+    reclaimable_bytes: u64,
+    older_than_ms: i64,
+}
+
+/// Streams files under the table location that nothing references, followed by a summary.
+///
+/// Only files last modified before `older_than_ms` are reported, so that files
+/// from in-flight commits aren't mistaken for orphans.
+pub async fn handle_orphans<W: Write>(
+    table: &Table,
+    older_than_ms: i64,
+    s3_client: Option<&aws_sdk_s3::Client>,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let referenced = referenced_files(table).await?;
+    let listed = list_objects(table.metadata().location(), s3_client).await?;
+    let listed_files = listed.len();
+
+    let mut orphans: Vec<ListedObject> = listed
+        .into_iter()
+        .filter(|object| !referenced.contains(&normalize(&object.path)))
+        .filter(|object| {
+            object
+                .last_modified_ms
+                .is_some_and(|modified| modified < older_than_ms)
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let summary = OrphanSummary {
+        listed_files,
+        referenced_files: referenced.len(),
+        orphan_files: orphans.len(),
+        reclaimable_bytes: orphans.iter().map(|object| object.size).sum(),
+        older_than_ms,
+    };
+
+    let records = orphans
+        .into_iter()
+        .map(|object| {
+            OrphanRecord::Orphan(OrphanFile {
+                path: object.path,
+                size: object.size,
+                last_modified_ms: object.last_modified_ms,
+            })
+        })
+        .chain(std::iter::once(OrphanRecord::Summary(summary)));
+    output.display_stream(stream::iter(records.map(Ok))).await
+}
+
+/// Collects every file the table references: metadata files, statistics files,
+/// and the manifest lists, manifests and data/delete files of every retained snapshot.
+///
+/// Manifests shared between snapshots are only read once.
+async fn referenced_files(table: &Table) -> Result<HashSet<String>> {
+    let metadata = table.metadata();
+    let location = metadata.location().trim_end_matches('/');
+    let mut referenced: HashSet<String> = HashSet::new();
+
+    referenced.extend(table.metadata_location().map(String::from));
+    referenced.extend(
+        metadata
+            .metadata_log()
+            .iter()
+            .map(|entry| entry.metadata_file.clone()),
+    );
+    // Tables written by the Hadoop catalog keep a pointer to the current metadata
+    // file, which no metadata references but which must not be deleted. Other
+    // catalogs don't write one, so only count it when it's there.
+    let version_hint = format!("{}/metadata/version-hint.text", location);
+    if table.file_io().exists(&version_hint).await? {
+        referenced.insert(version_hint);
+    }
+    referenced.extend(
+        metadata
+            .statistics_iter()
+            .map(|statistics| statistics.statistics_path.clone()),
+    );
+    referenced.extend(
+        metadata
+            .partition_statistics_iter()
+            .map(|statistics| statistics.statistics_path.clone()),
+    );
+
+    let mut manifests: BTreeMap<String, ManifestFile> = BTreeMap::new();
+    for snapshot in metadata.snapshots() {
+        referenced.insert(snapshot.manifest_list().to_string());
+        let manifest_list = load_manifest_list(table, snapshot)
+            .await
+            .with_context(|| format!("Failed to read snapshot {}", snapshot.snapshot_id()))?;
+        for manifest_file in manifest_list.consume_entries() {
+            manifests
+                .entry(manifest_file.manifest_path.clone())
+                .or_insert(manifest_file);
+        }
+    }
+
+    let file_io = table.file_io();
+    let mut loaded = stream::iter(manifests.into_values().map(|manifest_file| async move {
+        let manifest = manifest_file.load_manifest(file_io).await;
+        (manifest_file.manifest_path, manifest)
+    }))
+    .buffer_unordered(7);
+
+    while let Some((manifest_path, manifest)) = loaded.next().await {
+        let manifest =
+            manifest.with_context(|| format!("Failed to load manifest {}", manifest_path))?;
+        referenced.insert(manifest_path);
+        referenced.extend(
+            manifest
+                .entries()
+                .iter()
+                .filter(|entry| entry.status() != ManifestStatus::Deleted)
+                .map(|entry| entry.file_path().to_string()),
+        );
+    }
+
+    Ok(referenced.iter().map(|path| normalize(path)).collect())
+}
+
+/// Normalizes S3 scheme aliases and local `file:` URIs, so that listed and
+/// referenced paths compare equal.
+fn normalize(path: &str) -> String {
+    if let Some(rest) = ["s3a://", "s3n://"]
+        .iter()
+        .find_map(|scheme| path.strip_prefix(scheme))
+    {
+        return format!("s3://{}", rest);
+    }
+    // Local files may be written as file:///path, file:/path or plain /path
+    match path.strip_prefix("file:") {
+        Some(rest) => format!("/{}", rest.trim_start_matches('/')),
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TableCommands;
+    use crate::table_commands::{handle_table_command, load_table};
+    use crate::test_support::*;
+    use iceberg::io::FileIO;
+
+    /// Writes a local table with one snapshot referencing `a.parquet`, plus an
+    /// unreferenced `orphan.parquet` and a leftover manifest.
+    async fn create_local_table(root: &std::path::Path) -> Result<Table> {
+        let location = root.to_str().unwrap();
+        let file_io = FileIO::new_with_fs();
+
+        let mut metadata =
+            metadata_with_snapshot(123, &format!("{location}/metadata/snap-123.avro"));
+        metadata["location"] = serde_json::json!(location);
+        let metadata_location = format!("{location}/metadata/v1.metadata.json");
+        file_io
+            .new_output(&metadata_location)?
+            .write(serde_json::to_vec(&metadata)?.into())
+            .await?;
+
+        for name in ["a.parquet", "orphan.parquet"] {
+            file_io
+                .new_output(format!("{location}/data/{name}"))?
+                .write(bytes::Bytes::from_static(b"0123456789"))
+                .await?;
+        }
+
+        let manifest = write_manifest(
+            &file_io,
+            &format!("{location}/metadata/m1.avro"),
+            123,
+            vec![data_file(&format!("{location}/data/a.parquet"), 1, 10)],
+        )
+        .await;
+        write_manifest(
+            &file_io,
+            &format!("{location}/metadata/stale.avro"),
+            123,
+            vec![],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            &format!("{location}/metadata/snap-123.avro"),
+            123,
+            None,
+            1,
+            vec![manifest],
+        )
+        .await;
+
+        load_table(&file_io, &metadata_location).await
+    }
+
+    /// A cutoff after every file in the test tables was written
+    const EVERYTHING: &str = "2100-01-01T00:00:00Z";
+
+    async fn orphans(table: &Table, older_than: &str) -> Result<Vec<serde_json::Value>> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            table,
+            TableCommands::Orphans {
+                older_than: older_than.to_string(),
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        Ok(output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    #[tokio::test]
+    async fn test_orphans() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let table = create_local_table(root.path()).await?;
+        let location = root.path().to_str().unwrap();
+
+        let records = orphans(&table, EVERYTHING).await?;
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["kind"], "orphan");
+        assert_eq!(
+            records[0]["path"],
+            format!("{location}/data/orphan.parquet")
+        );
+        assert_eq!(records[0]["size"], 10);
+        assert!(records[0]["last-modified-ms"].is_i64());
+        assert_eq!(
+            records[1]["path"],
+            format!("{location}/metadata/stale.avro")
+        );
+
+        let summary = &records[2];
+        assert_eq!(summary["kind"], "summary");
+        assert_eq!(summary["listed-files"], 6);
+        assert_eq!(summary["orphan-files"], 2);
+        assert_eq!(
+            summary["reclaimable-bytes"],
+            10 + records[1]["size"].as_u64().unwrap()
+        );
+
+        // Everything was just written, so nothing is older than the default of three days
+        let records = orphans(&table, "-3d").await?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["orphan-files"], 0);
+        assert!(records[0]["older-than-ms"].is_i64());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_orphans_version_hint() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let table = create_local_table(root.path()).await?;
+        let location = root.path().to_str().unwrap();
+
+        let without_hint = orphans(&table, EVERYTHING).await?;
+        std::fs::write(root.path().join("metadata/version-hint.text"), "1")?;
+        let with_hint = orphans(&table, EVERYTHING).await?;
+
+        // The hint is listed and referenced, so it's never an orphan
+        let summary = |records: &[serde_json::Value]| records.last().unwrap().clone();
+        assert_eq!(summary(&without_hint)["referenced-files"], 4);
+        assert_eq!(summary(&with_hint)["referenced-files"], 5);
+        assert_eq!(summary(&with_hint)["listed-files"], 7);
+        assert_eq!(summary(&with_hint)["orphan-files"], 2);
+        assert!(
+            with_hint
+                .iter()
+                .all(|record| record["path"] != format!("{location}/metadata/version-hint.text"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("s3a://bucket/key"), "s3://bucket/key");
+        assert_eq!(normalize("s3://bucket/key"), "s3://bucket/key");
+        assert_eq!(normalize("/tmp/table/key"), "/tmp/table/key");
+        assert_eq!(normalize("file:///tmp/table/key"), "/tmp/table/key");
+        assert_eq!(normalize("file:/tmp/table/key"), "/tmp/table/key");
+    }
+}
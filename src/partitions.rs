@@ -0,0 +1,244 @@
+//! Summarizes a snapshot's live files per partition.
+//!
+//! Mirrors Iceberg's `partitions` metadata table: file, record and byte totals
+//! for data and delete files, plus when each partition was last written.
+
+use crate::cli::ContentFilter;
+use crate::table_commands::iterate_files;
+use crate::terminal_output::TerminalOutput;
+use crate::values::partition_to_json;
+use anyhow::Result;
+use futures::{TryStreamExt, stream};
+use iceberg::spec::{DataContentType, ManifestEntryRef, Snapshot, TableMetadata};
+use iceberg::table::Table;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Totals for one partition tuple of one partition spec.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionInfo {
+    partition: serde_json::Map<String, serde_json::Value>,
+    spec_id: i32,
+    record_count: u64,
+    file_count: u64,
+    total_data_file_size_in_bytes: u64,
+    position_delete_record_count: u64,
+    position_delete_file_count: u64,
+    equality_delete_record_count: u64,
+    equality_delete_file_count: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_updated_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_updated_snapshot_id: Option<i64>,
+}
+
+impl PartitionInfo {
+    fn new(partition: serde_json::Map<String, serde_json::Value>, spec_id: i32) -> Self {
+        Self {
+            partition,
+            spec_id,
+            record_count: 0,
+            file_count: 0,
+            total_data_file_size_in_bytes: 0,
+            position_delete_record_count: 0,
+            position_delete_file_count: 0,
+            equality_delete_record_count: 0,
+            equality_delete_file_count: 0,
+            last_updated_at: None,
+            last_updated_snapshot_id: None,
+        }
+    }
+
+    fn add(&mut self, entry: &ManifestEntryRef, metadata: &TableMetadata) {
+        let data_file = entry.data_file();
+        match data_file.content_type() {
+            DataContentType::Data => {
+                self.record_count += data_file.record_count();
+                self.file_count += 1;
+                self.total_data_file_size_in_bytes += data_file.file_size_in_bytes();
+            }
+            DataContentType::PositionDeletes => {
+                self.position_delete_record_count += data_file.record_count();
+                self.position_delete_file_count += 1;
+            }
+            DataContentType::EqualityDeletes => {
+                self.equality_delete_record_count += data_file.record_count();
+                self.equality_delete_file_count += 1;
+            }
+        }
+
+        // The writing snapshot may have expired, in which case only its ID is known
+        let updated_at = entry
+            .snapshot_id()
+            .and_then(|snapshot_id| metadata.snapshot_by_id(snapshot_id))
+            .map(|snapshot| snapshot.timestamp_ms());
+        if (updated_at, entry.snapshot_id()) > (self.last_updated_at, self.last_updated_snapshot_id)
+        {
+            self.last_updated_at = updated_at;
+            self.last_updated_snapshot_id = entry.snapshot_id();
+        }
+    }
+}
+
+/// Streams one summary per partition of the snapshot's live files, ordered by spec and partition.
+pub async fn handle_partitions<W: Write>(
+    table: &Table,
+    snapshot: &Snapshot,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let metadata = table.metadata();
+    let mut partitions: BTreeMap<(i32, String), PartitionInfo> = BTreeMap::new();
+
+    let mut files = Box::pin(iterate_files(
+        table,
+        snapshot,
+        ContentFilter::All,
+        None,
+        false,
+    ));
+    while let Some(record) = files.try_next().await? {
+        let (Some(entry), Some(spec_id)) = (record.entry, record.partition_spec_id) else {
+            continue;
+        };
+        let partition = partition_to_json(metadata, spec_id, entry.data_file().partition());
+        let key = (spec_id, serde_json::to_string(&partition)?);
+        partitions
+            .entry(key)
+            .or_insert_with(|| PartitionInfo::new(partition, spec_id))
+            .add(&entry, metadata);
+    }
+
+    output
+        .display_stream(stream::iter(partitions.into_values().map(Ok)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{SnapshotCmd, TableCommands};
+    use crate::table_commands::{handle_table_command, load_table};
+    use crate::test_support::*;
+    use iceberg::spec::PartitionSpec;
+
+    #[tokio::test]
+    async fn test_partitions() -> Result<()> {
+        let metadata_json = serde_json::to_string(&day_partitioned_metadata(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let day_manifest = write_manifest_for_spec(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            day_partition_spec(),
+            vec![
+                day_partitioned_data_file(
+                    "s3://bucket/table/data/a.parquet",
+                    "2026-10-02",
+                    10,
+                    1000,
+                ),
+                day_partitioned_data_file(
+                    "s3://bucket/table/data/b.parquet",
+                    "2026-10-01",
+                    20,
+                    2000,
+                ),
+                day_partitioned_data_file(
+                    "s3://bucket/table/data/c.parquet",
+                    "2026-10-01",
+                    30,
+                    3000,
+                ),
+            ],
+        )
+        .await;
+        // Files written before the table was partitioned by day
+        let unpartitioned_manifest = write_manifest_for_spec(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            PartitionSpec::unpartition_spec(),
+            vec![data_file("s3://bucket/table/data/d.parquet", 5, 500)],
+        )
+        .await;
+        let delete_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m3.avro",
+            123,
+            vec![position_delete_file(
+                "s3://bucket/table/data/e.parquet",
+                2,
+                200,
+            )],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![day_manifest, unpartitioned_manifest, delete_manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Partitions,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let partitions: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(partitions.len(), 3);
+
+        assert_eq!(partitions[0]["spec-id"], 0);
+        assert_eq!(partitions[0]["partition"], serde_json::json!({}));
+        assert_eq!(partitions[0]["file-count"], 1);
+        assert_eq!(partitions[0]["record-count"], 5);
+        assert_eq!(partitions[0]["position-delete-file-count"], 1);
+        assert_eq!(partitions[0]["position-delete-record-count"], 2);
+
+        assert_eq!(
+            partitions[1],
+            serde_json::json!({
+                "partition": {"event_day": "2026-10-01"},
+                "spec-id": 1,
+                "record-count": 50,
+                "file-count": 2,
+                "total-data-file-size-in-bytes": 5000,
+                "position-delete-record-count": 0,
+                "position-delete-file-count": 0,
+                "equality-delete-record-count": 0,
+                "equality-delete-file-count": 0,
+                "last-updated-at": 1600000000000_i64,
+                "last-updated-snapshot-id": 123
+            })
+        );
+
+        assert_eq!(partitions[2]["partition"]["event_day"], "2026-10-02");
+        assert_eq!(partitions[2]["file-count"], 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+//! Field selection (`--fields`) and filtering (`--where`) of output records.
+//!
+//! Both work on the serialized form of a record, so field paths use the same names
+//! as the JSON output, e.g. `summary.operation` or `timestamp-ms`.
+
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// Selects and filters records before they're formatted
+#[derive(Clone, Debug, Default)]
+pub struct RecordFilter {
+    fields: Vec<String>,
+    conditions: Vec<Condition>,
+}
+
+impl RecordFilter {
+    pub fn new(fields: Vec<String>, conditions: Vec<Condition>) -> Self {
+        Self { fields, conditions }
+    }
+
+    /// Returns the selected fields of the record, or `None` if it doesn't match every condition.
+    ///
+    /// Records that aren't objects have no fields to select, so they're kept whole.
+    pub fn apply(&self, record: Value) -> Option<Value> {
+        if !self.conditions.iter().all(|c| c.matches(&record)) {
+            return None;
+        }
+        if self.fields.is_empty() {
+            return Some(record);
+        }
+        let Value::Object(map) = &record else {
+            return Some(record);
+        };
+        let mut selected = Map::new();
+        for field in &self.fields {
+            if let Some((keys, value)) = lookup(map, field) {
+                insert(&mut selected, &keys, value.clone());
+            }
+        }
+        Some(Value::Object(selected))
+    }
+}
+
+/// A comparison between a field and a literal, e.g. `summary.operation == "overwrite"`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    field: String,
+    operator: Operator,
+    literal: Value,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Operator {
+    // Two-character operators come first so `>=` isn't read as `>`
+    const ALL: [(&'static str, Operator); 6] = [
+        ("==", Operator::Eq),
+        ("!=", Operator::Ne),
+        ("<=", Operator::Le),
+        (">=", Operator::Ge),
+        ("<", Operator::Lt),
+        (">", Operator::Gt),
+    ];
+}
+
+impl Condition {
+    /// Missing fields compare as null; ordering only applies to two numbers or two strings.
+    pub fn matches(&self, record: &Value) -> bool {
+        let value = match record {
+            Value::Object(map) => lookup(map, &self.field).map(|(_, value)| value),
+            _ => None,
+        }
+        .unwrap_or(&Value::Null);
+        match (self.operator, compare(value, &self.literal)) {
+            (Operator::Eq, ordering) => ordering == Some(Ordering::Equal),
+            (Operator::Ne, ordering) => ordering != Some(Ordering::Equal),
+            (_, None) => false,
+            (Operator::Lt, Some(ordering)) => ordering.is_lt(),
+            (Operator::Le, Some(ordering)) => ordering.is_le(),
+            (Operator::Gt, Some(ordering)) => ordering.is_gt(),
+            (Operator::Ge, Some(ordering)) => ordering.is_ge(),
+        }
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    /// Parses `<field> <operator> <literal>`, where the literal is JSON (`"text"`, `42`,
+    /// `true`, `null`) or a bare word, which is read as a string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (position, symbol, operator) = (0..s.len())
+            .filter(|&i| s.is_char_boundary(i))
+            .find_map(|i| {
+                Operator::ALL
+                    .iter()
+                    .find(|(symbol, _)| s[i..].starts_with(symbol))
+                    .map(|&(symbol, operator)| (i, symbol, operator))
+            })
+            .ok_or_else(|| {
+                format!(
+                    "expected a comparison like 'field == value', using one of: {}",
+                    Operator::ALL.map(|(symbol, _)| symbol).join(" ")
+                )
+            })?;
+        let field = s[..position].trim();
+        if field.is_empty() {
+            return Err("missing a field before the comparison".to_string());
+        }
+        let literal = s[position + symbol.len()..].trim();
+        if literal.is_empty() {
+            return Err("missing a value after the comparison".to_string());
+        }
+        let literal =
+            serde_json::from_str(literal).unwrap_or_else(|_| Value::String(literal.to_string()));
+        Ok(Self {
+            field: field.to_string(),
+            operator,
+            literal,
+        })
+    }
+}
+
+/// Finds a dotted path, along with the keys it matched.
+///
+/// Keys may themselves contain dots (e.g. table properties like `write.format.default`),
+/// so each level tries the whole remaining path as a key before splitting it.
+fn lookup<'a>(map: &'a Map<String, Value>, path: &str) -> Option<(Vec<&'a str>, &'a Value)> {
+    if let Some((key, value)) = map.get_key_value(path) {
+        return Some((vec![key.as_str()], value));
+    }
+    path.match_indices('.').find_map(|(i, _)| {
+        let (key, value) = map.get_key_value(&path[..i])?;
+        let Value::Object(nested) = value else {
+            return None;
+        };
+        let (mut keys, value) = lookup(nested, &path[i + 1..])?;
+        keys.insert(0, key.as_str());
+        Some((keys, value))
+    })
+}
+
+fn insert(map: &mut Map<String, Value>, keys: &[&str], value: Value) {
+    match keys {
+        [] => {}
+        [key] => {
+            map.insert(key.to_string(), value);
+        }
+        [key, rest @ ..] => {
+            let nested = map
+                .entry(key.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            if let Value::Object(nested) = nested {
+                insert(nested, rest, value);
+            }
+        }
+    }
+}
+
+fn compare(a: &Value, b: &Value) -> Option<Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => Some(a.cmp(&b)),
+            _ => a.as_f64()?.partial_cmp(&b.as_f64()?),
+        },
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        (a, b) if a == b => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot() -> Value {
+        json!({
+            "snapshot-id": 1,
+            "timestamp-ms": 1700000000001i64,
+            "summary": {"operation": "overwrite", "added-data-files": "3"},
+            "properties": {"write.format.default": "parquet"},
+        })
+    }
+
+    fn filter(fields: &[&str], conditions: &[&str]) -> RecordFilter {
+        RecordFilter::new(
+            fields.iter().map(|f| f.to_string()).collect(),
+            conditions.iter().map(|c| c.parse().unwrap()).collect(),
+        )
+    }
+
+    #[test]
+    fn test_fields_keep_nesting() {
+        let selected = filter(
+            &[
+                "snapshot-id",
+                "summary.operation",
+                "properties.write.format.default",
+                "missing",
+            ],
+            &[],
+        )
+        .apply(snapshot());
+
+        assert_eq!(
+            selected,
+            Some(json!({
+                "snapshot-id": 1,
+                "summary": {"operation": "overwrite"},
+                "properties": {"write.format.default": "parquet"},
+            }))
+        );
+    }
+
+    #[test]
+    fn test_conditions() {
+        let matches = |condition: &str| filter(&[], &[condition]).apply(snapshot()).is_some();
+
+        assert!(matches(r#"summary.operation == "overwrite""#));
+        assert!(matches("summary.operation==overwrite"));
+        assert!(matches("summary.operation != append"));
+        assert!(matches("timestamp-ms > 1700000000000"));
+        assert!(!matches("timestamp-ms <= 1700000000000"));
+        assert!(matches("snapshot-id >= 1.0"));
+        assert!(matches("parent-snapshot-id == null"));
+        // Numbers and strings don't order against each other
+        assert!(!matches("summary.added-data-files > 2"));
+        assert!(matches(r#"summary.added-data-files == "3""#));
+    }
+
+    #[test]
+    fn test_all_conditions_must_match() {
+        let filter = filter(&[], &["snapshot-id == 1", "summary.operation == append"]);
+        assert_eq!(filter.apply(snapshot()), None);
+    }
+
+    #[test]
+    fn test_scalars_pass_through_fields() {
+        assert_eq!(filter(&["name"], &[]).apply(json!("db")), Some(json!("db")));
+    }
+
+    #[test]
+    fn test_invalid_conditions() {
+        assert!("summary.operation".parse::<Condition>().is_err());
+        assert!("== 1".parse::<Condition>().is_err());
+        assert!("snapshot-id >".parse::<Condition>().is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! Compares the live files of two snapshots.
+//!
+//! Skips the manifests both snapshots' manifest lists share, as they add and
+//! remove nothing, then collects the data and delete files in the rest and
+//! reports what was added and removed going from one to the other.
+
+use crate::table_commands::{FileContent, load_manifest_list};
+use crate::terminal_output::TerminalOutput;
+use anyhow::{Context, Result};
+use futures::{StreamExt, TryStreamExt, stream};
+use iceberg::spec::{ManifestEntryRef, ManifestFile, ManifestStatus, Snapshot};
+use iceberg::table::Table;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+/// How many manifests are loaded at once
+const MANIFEST_CONCURRENCY: usize = 7;
+
+/// One line of `diff` output: a file that changed, or the final summary.
+#[derive(Debug, Serialize)]
+#[serde(tag = "change", rename_all = "kebab-case")]
+enum DiffRecord {
+    Added(DiffFile),
+    Removed(DiffFile),
+    Summary(DiffSummary),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DiffFile {
+    content: FileContent,
+    path: String,
+    record_count: u64,
+    file_size_in_bytes: u64,
+}
+
+impl DiffFile {
+    fn from_entry(entry: &ManifestEntryRef) -> Self {
+        Self {
+            content: entry.content_type().into(),
+            path: entry.file_path().to_string(),
+            record_count: entry.record_count(),
+            file_size_in_bytes: entry.file_size_in_bytes(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DiffTotals {
+    files: u64,
+    records: u64,
+    bytes: u64,
+}
+
+impl DiffTotals {
+    fn add(&mut self, file: &DiffFile) {
+        self.files += 1;
+        self.records += file.record_count;
+        self.bytes += file.file_size_in_bytes;
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DiffChanges {
+    added: DiffTotals,
+    removed: DiffTotals,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct DiffSummary {
+    from_snapshot_id: i64,
+    to_snapshot_id: i64,
+    data_files: DiffChanges,
+    delete_files: DiffChanges,
+}
+
+impl DiffSummary {
+    fn new(from: &Snapshot, to: &Snapshot) -> Self {
+        Self {
+            from_snapshot_id: from.snapshot_id(),
+            to_snapshot_id: to.snapshot_id(),
+            data_files: DiffChanges::default(),
+            delete_files: DiffChanges::default(),
+        }
+    }
+
+    fn changes(&mut self, content: FileContent) -> &mut DiffChanges {
+        match content {
+            FileContent::Data => &mut self.data_files,
+            FileContent::Deletes => &mut self.delete_files,
+        }
+    }
+
+    fn add(&mut self, record: &DiffRecord) {
+        match record {
+            DiffRecord::Added(file) => self.changes(file.content).added.add(file),
+            DiffRecord::Removed(file) => self.changes(file.content).removed.add(file),
+            DiffRecord::Summary(_) => {}
+        }
+    }
+}
+
+/// Streams the files added and removed between two snapshots, followed by a summary.
+pub async fn handle_diff<W: Write>(
+    table: &Table,
+    from: &Snapshot,
+    to: &Snapshot,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let from_manifests = load_manifest_list(table, from).await?;
+    let to_manifests = load_manifest_list(table, to).await?;
+    let from_paths = manifest_paths(from_manifests.entries());
+    let to_paths = manifest_paths(to_manifests.entries());
+
+    let from_files = live_files(table, from_manifests.entries(), &to_paths).await?;
+    let to_files = live_files(table, to_manifests.entries(), &from_paths).await?;
+
+    let added = to_files
+        .iter()
+        .filter(|(path, _)| !from_files.contains_key(*path))
+        .map(|(_, entry)| DiffRecord::Added(DiffFile::from_entry(entry)));
+    let removed = from_files
+        .iter()
+        .filter(|(path, _)| !to_files.contains_key(*path))
+        .map(|(_, entry)| DiffRecord::Removed(DiffFile::from_entry(entry)));
+
+    let mut summary = DiffSummary::new(from, to);
+    let records: Vec<DiffRecord> = added
+        .chain(removed)
+        .inspect(|record| summary.add(record))
+        .collect();
+
+    let diff_stream = stream::iter(
+        records
+            .into_iter()
+            .chain(std::iter::once(DiffRecord::Summary(summary)))
+            .map(Ok),
+    );
+    output.display_stream(diff_stream).await
+}
+
+fn manifest_paths(manifests: &[ManifestFile]) -> HashSet<&str> {
+    manifests
+        .iter()
+        .map(|manifest_file| manifest_file.manifest_path.as_str())
+        .collect()
+}
+
+/// Collects the live data and delete files in a snapshot's manifests, keyed by path,
+/// skipping the manifests in `shared`.
+async fn live_files(
+    table: &Table,
+    manifests: &[ManifestFile],
+    shared: &HashSet<&str>,
+) -> Result<BTreeMap<String, ManifestEntryRef>> {
+    let file_io = table.file_io();
+    // Load via the manifest list entry so that entries inherit sequence numbers
+    let mut loads = stream::iter(
+        manifests
+            .iter()
+            .filter(|manifest_file| !shared.contains(manifest_file.manifest_path.as_str()))
+            .map(|manifest_file| async move {
+                manifest_file.load_manifest(file_io).await.with_context(|| {
+                    format!("Failed to load manifest {}", manifest_file.manifest_path)
+                })
+            }),
+    )
+    .buffered(MANIFEST_CONCURRENCY);
+
+    let mut files = BTreeMap::new();
+    while let Some(manifest) = loads.try_next().await? {
+        for entry in manifest.entries() {
+            if entry.status() != ManifestStatus::Deleted {
+                files.insert(entry.file_path().to_string(), entry.clone());
+            }
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TableCommands;
+    use crate::table_commands::{handle_table_command, load_table};
+    use crate::test_support::*;
+
+    /// Writes a table where snapshot 456 removes `a.parquet`, keeps `b.parquet`,
+    /// and adds `c.parquet` plus a position delete file.
+    async fn create_table() -> Result<Table> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let a = data_file("s3://bucket/table/data/a.parquet", 10, 1000);
+        let b = data_file("s3://bucket/table/data/b.parquet", 20, 2000);
+        let c = data_file("s3://bucket/table/data/c.parquet", 30, 3000);
+        let d = position_delete_file("s3://bucket/table/data/d.parquet", 2, 200);
+
+        let m1 = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![a, b.clone()],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![m1],
+        )
+        .await;
+
+        let m2 = write_manifest(&file_io, "s3://bucket/table/m2.avro", 456, vec![b, c]).await;
+        let m3 = write_manifest(&file_io, "s3://bucket/table/m3.avro", 456, vec![d]).await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-456.avro",
+            456,
+            Some(123),
+            2,
+            vec![m2, m3],
+        )
+        .await;
+
+        load_table(&file_io, location).await
+    }
+
+    async fn diff(table: &Table, from: &str, to: &str) -> Result<Vec<serde_json::Value>> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            table,
+            TableCommands::Diff {
+                from: from.to_string(),
+                to: to.to_string(),
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        Ok(output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    #[tokio::test]
+    async fn test_diff() -> Result<()> {
+        let table = create_table().await?;
+
+        let records = diff(&table, "123", "current").await?;
+
+        assert_eq!(records.len(), 4);
+
+        assert_eq!(records[0]["change"], "added");
+        assert_eq!(records[0]["content"], "data");
+        assert_eq!(records[0]["path"], "s3://bucket/table/data/c.parquet");
+        assert_eq!(records[0]["record-count"], 30);
+        assert_eq!(records[0]["file-size-in-bytes"], 3000);
+
+        assert_eq!(records[1]["change"], "added");
+        assert_eq!(records[1]["content"], "deletes");
+        assert_eq!(records[1]["path"], "s3://bucket/table/data/d.parquet");
+
+        assert_eq!(records[2]["change"], "removed");
+        assert_eq!(records[2]["path"], "s3://bucket/table/data/a.parquet");
+
+        let summary = &records[3];
+        assert_eq!(summary["change"], "summary");
+        assert_eq!(summary["from-snapshot-id"], 123);
+        assert_eq!(summary["to-snapshot-id"], 456);
+        assert_eq!(
+            summary["data-files"],
+            serde_json::json!({
+                "added": {"files": 1, "records": 30, "bytes": 3000},
+                "removed": {"files": 1, "records": 10, "bytes": 1000}
+            })
+        );
+        assert_eq!(
+            summary["delete-files"],
+            serde_json::json!({
+                "added": {"files": 1, "records": 2, "bytes": 200},
+                "removed": {"files": 0, "records": 0, "bytes": 0}
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diff_skips_shared_manifests() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let a = data_file("s3://bucket/table/data/a.parquet", 10, 1000);
+        let c = data_file("s3://bucket/table/data/c.parquet", 30, 3000);
+        let m1 = write_manifest(&file_io, "s3://bucket/table/m1.avro", 123, vec![a]).await;
+        let m2 = write_manifest(&file_io, "s3://bucket/table/m2.avro", 456, vec![c]).await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![m1.clone()],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-456.avro",
+            456,
+            Some(123),
+            2,
+            vec![m1, m2],
+        )
+        .await;
+        // Both snapshots list m1, so it's never read
+        file_io.delete("s3://bucket/table/m1.avro").await?;
+        let table = load_table(&file_io, location).await?;
+
+        let records = diff(&table, "123", "456").await?;
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["change"], "added");
+        assert_eq!(records[0]["path"], "s3://bucket/table/data/c.parquet");
+        assert_eq!(records[1]["data-files"]["removed"]["files"], 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_diff_same_snapshot() -> Result<()> {
+        let table = create_table().await?;
+
+        let records = diff(&table, "main", "456").await?;
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["change"], "summary");
+        assert_eq!(records[0]["data-files"]["added"]["files"], 0);
+        assert_eq!(records[0]["data-files"]["removed"]["files"], 0);
+
+        Ok(())
+    }
+}
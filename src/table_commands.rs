@@ -1,33 +1,232 @@
-use crate::cli::{SnapshotCmd, TableCommands};
+use crate::cli::{ContentFilter, FilesArgs, SnapshotCmd, TableCommands};
+use crate::deep_verify::check_data_file;
 use crate::error::ExpectedError;
-use crate::file_existence::{FileExistenceChecker, create_existence_checker};
+use crate::file_existence::{
+    FileExistenceChecker, FileIOExistenceChecker, create_existence_checker,
+};
+use crate::health::handle_health;
+use crate::orphans::handle_orphans;
+use crate::partitions::handle_partitions;
+use crate::snapshot_diff::handle_diff;
 use crate::terminal_output::TerminalOutput;
+use crate::values::{bound_to_json, datum_to_json, partition_to_json, partition_type};
+use crate::verify::{VerifyScope, handle_verify};
+use crate::verify_report::VerificationReport;
 use anyhow::{Context, Result};
 use async_stream::try_stream;
-use futures::{Stream, StreamExt, stream};
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use futures::{Stream, StreamExt, TryStreamExt, stream};
 use iceberg::TableIdent;
 use iceberg::io::FileIO;
-use iceberg::spec::{Manifest, ManifestList, Snapshot, Summary, TableMetadata};
+use iceberg::spec::{
+    DataContentType, DataFile, DataFileFormat, ManifestContentType, ManifestEntryRef, ManifestFile,
+    ManifestList, NullOrder, Operation, PartitionSpec, Snapshot, SnapshotRef, SnapshotReference,
+    SortDirection, SortOrder, Summary, TableMetadata, Transform,
+};
 use iceberg::table::{StaticTable, Table};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Write;
+use std::time::Instant;
 use tracing::instrument;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum FileType {
     Metadata,
     ManifestList,
     Manifest,
     Data,
+    PositionDelete,
+    EqualityDelete,
+    DeletionVector,
+}
+
+impl FileType {
+    /// Classifies a manifest entry's file; deletion vectors are position deletes stored in Puffin.
+    pub(crate) fn of(data_file: &DataFile) -> Self {
+        match (data_file.content_type(), data_file.file_format()) {
+            (DataContentType::Data, _) => FileType::Data,
+            (DataContentType::PositionDeletes, DataFileFormat::Puffin) => FileType::DeletionVector,
+            (DataContentType::PositionDeletes, _) => FileType::PositionDelete,
+            (DataContentType::EqualityDeletes, _) => FileType::EqualityDelete,
+        }
+    }
+}
+
+/// Whether a file holds table data or deletes against it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FileContent {
+    Data,
+    Deletes,
+}
+
+impl From<DataContentType> for FileContent {
+    fn from(content_type: DataContentType) -> Self {
+        match content_type {
+            DataContentType::Data => FileContent::Data,
+            DataContentType::PositionDeletes | DataContentType::EqualityDeletes => {
+                FileContent::Deletes
+            }
+        }
+    }
+}
+
+impl From<ManifestContentType> for FileContent {
+    fn from(content_type: ManifestContentType) -> Self {
+        match content_type {
+            ManifestContentType::Data => FileContent::Data,
+            ManifestContentType::Deletes => FileContent::Deletes,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
-struct FileRecord {
-    r#type: FileType,
-    path: String,
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct FileRecord {
+    pub(crate) r#type: FileType,
+    pub(crate) path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    exists: Option<bool>,
+    pub(crate) exists: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) size_mismatch: Option<SizeMismatch>,
+    /// Problems found by `--deep` verification
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) failures: Vec<String>,
+    /// For position deletes and deletion vectors scoped to a single data file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) referenced_data_file: Option<String>,
+    /// For equality deletes, the field IDs used to match rows
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) equality_ids: Option<Vec<i32>>,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub(crate) details: Option<FileDetails>,
+    /// The manifest entry behind a data file, for callers that need more than its path
+    #[serde(skip)]
+    pub(crate) entry: Option<ManifestEntryRef>,
+    /// The partition spec of the manifest the entry came from
+    #[serde(skip)]
+    pub(crate) partition_spec_id: Option<i32>,
+    /// The manifest list entry behind a manifest, for its counts and length
+    #[serde(skip)]
+    pub(crate) manifest_file: Option<ManifestFile>,
+}
+
+/// A file whose size differs from the one recorded in its manifest, usually a truncated upload.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SizeMismatch {
+    expected: u64,
+    actual: u64,
+}
+
+impl FileRecord {
+    /// A record for a metadata file, with no manifest entry behind it.
+    fn new(r#type: FileType, path: String, exists: Option<bool>) -> Self {
+        Self {
+            r#type,
+            path,
+            exists,
+            size_mismatch: None,
+            failures: Vec::new(),
+            referenced_data_file: None,
+            equality_ids: None,
+            details: None,
+            entry: None,
+            partition_spec_id: None,
+            manifest_file: None,
+        }
+    }
+}
+
+/// What a data file's rows represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DataFileContent {
+    Data,
+    PositionDeletes,
+    EqualityDeletes,
+}
+
+impl From<DataContentType> for DataFileContent {
+    fn from(content_type: DataContentType) -> Self {
+        match content_type {
+            DataContentType::Data => DataFileContent::Data,
+            DataContentType::PositionDeletes => DataFileContent::PositionDeletes,
+            DataContentType::EqualityDeletes => DataFileContent::EqualityDeletes,
+        }
+    }
+}
+
+/// Per-file statistics from a manifest entry, for `files --details`.
+///
+/// Column statistics are keyed by column name, falling back to the field ID
+/// for columns no schema knows about.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct FileDetails {
+    content: DataFileContent,
+    file_format: DataFileFormat,
+    record_count: u64,
+    file_size_in_bytes: u64,
+    partition_spec_id: i32,
+    partition: serde_json::Map<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sequence_number: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    file_sequence_number: Option<i64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    lower_bounds: BTreeMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    upper_bounds: BTreeMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    null_value_counts: BTreeMap<String, u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    value_counts: BTreeMap<String, u64>,
+}
+
+impl FileDetails {
+    fn from_entry(
+        entry: &ManifestEntryRef,
+        partition_spec_id: i32,
+        metadata: &TableMetadata,
+    ) -> Self {
+        let data_file = entry.data_file();
+        let name = |field_id: &i32| {
+            column_name(metadata, *field_id).unwrap_or_else(|| field_id.to_string())
+        };
+
+        Self {
+            content: data_file.content_type().into(),
+            file_format: data_file.file_format(),
+            record_count: data_file.record_count(),
+            file_size_in_bytes: data_file.file_size_in_bytes(),
+            partition_spec_id,
+            partition: partition_to_json(metadata, partition_spec_id, data_file.partition()),
+            sequence_number: entry.sequence_number(),
+            file_sequence_number: entry.file_sequence_number,
+            lower_bounds: data_file
+                .lower_bounds()
+                .iter()
+                .map(|(field_id, datum)| (name(field_id), datum_to_json(datum)))
+                .collect(),
+            upper_bounds: data_file
+                .upper_bounds()
+                .iter()
+                .map(|(field_id, datum)| (name(field_id), datum_to_json(datum)))
+                .collect(),
+            null_value_counts: data_file
+                .null_value_counts()
+                .iter()
+                .map(|(field_id, count)| (name(field_id), *count))
+                .collect(),
+            value_counts: data_file
+                .value_counts()
+                .iter()
+                .map(|(field_id, count)| (name(field_id), *count))
+                .collect(),
+        }
+    }
 }
 
 /// Serializable view of an Iceberg Snapshot.
@@ -62,6 +261,351 @@ impl SnapshotInfo {
     }
 }
 
+/// An entry in a snapshot's manifest list, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct ManifestInfo {
+    path: String,
+    length: i64,
+    content: FileContent,
+    partition_spec_id: i32,
+    added_snapshot_id: i64,
+    sequence_number: i64,
+    min_sequence_number: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    added_files_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    existing_files_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_files_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    added_rows_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    existing_rows_count: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deleted_rows_count: Option<u64>,
+    partitions: Vec<PartitionSummaryInfo>,
+}
+
+/// The range of values a manifest holds for one partition field.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionSummaryInfo {
+    name: String,
+    contains_null: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    contains_nan: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lower_bound: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    upper_bound: Option<serde_json::Value>,
+}
+
+impl ManifestInfo {
+    fn from_manifest_file(manifest_file: &ManifestFile, metadata: &TableMetadata) -> Self {
+        let spec = metadata.partition_spec_by_id(manifest_file.partition_spec_id);
+        let fields = spec.map(|spec| spec.fields()).unwrap_or_default();
+        let partition_type = spec.and_then(|spec| partition_type(metadata, spec));
+        let field_type = |index: usize| {
+            partition_type
+                .as_ref()
+                .and_then(|partition_type| partition_type.fields().get(index))
+                .map(|field| field.field_type.as_ref())
+        };
+
+        let partitions = manifest_file
+            .partitions
+            .iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, summary)| PartitionSummaryInfo {
+                name: fields
+                    .get(index)
+                    .map(|field| field.name.clone())
+                    .unwrap_or_else(|| index.to_string()),
+                contains_null: summary.contains_null,
+                contains_nan: summary.contains_nan,
+                lower_bound: summary
+                    .lower_bound
+                    .as_ref()
+                    .map(|bound| bound_to_json(bound, field_type(index))),
+                upper_bound: summary
+                    .upper_bound
+                    .as_ref()
+                    .map(|bound| bound_to_json(bound, field_type(index))),
+            })
+            .collect();
+
+        Self {
+            path: manifest_file.manifest_path.clone(),
+            length: manifest_file.manifest_length,
+            content: manifest_file.content.into(),
+            partition_spec_id: manifest_file.partition_spec_id,
+            added_snapshot_id: manifest_file.added_snapshot_id,
+            sequence_number: manifest_file.sequence_number,
+            min_sequence_number: manifest_file.min_sequence_number,
+            added_files_count: manifest_file.added_files_count,
+            existing_files_count: manifest_file.existing_files_count,
+            deleted_files_count: manifest_file.deleted_files_count,
+            added_rows_count: manifest_file.added_rows_count,
+            existing_rows_count: manifest_file.existing_rows_count,
+            deleted_rows_count: manifest_file.deleted_rows_count,
+            partitions,
+        }
+    }
+}
+
+/// A snapshot's place in the table's lineage, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct LineageInfo {
+    snapshot_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_snapshot_id: Option<i64>,
+    timestamp_ms: i64,
+    operation: Operation,
+    /// The parent snapshot has been expired from the metadata
+    parent_expired: bool,
+    /// The snapshot is an ancestor of (or is) the current snapshot
+    main_line: bool,
+    /// More than one snapshot has this snapshot as its parent
+    branch_point: bool,
+    /// Following parents from this snapshot loops back to it (corrupt metadata)
+    in_cycle: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refs: Vec<String>,
+}
+
+/// Parent/child relationships between a table's snapshots.
+struct SnapshotGraph<'a> {
+    metadata: &'a TableMetadata,
+    child_counts: HashMap<i64, usize>,
+    main_line: HashSet<i64>,
+    cycles: HashSet<i64>,
+    refs: HashMap<i64, Vec<String>>,
+}
+
+impl<'a> SnapshotGraph<'a> {
+    fn new(metadata: &'a TableMetadata) -> Result<Self> {
+        let child_counts = metadata
+            .snapshots()
+            .filter_map(|snapshot| snapshot.parent_snapshot_id())
+            .fold(HashMap::new(), |mut counts, parent_id| {
+                *counts.entry(parent_id).or_insert(0) += 1;
+                counts
+            });
+
+        let main_line = current_ancestor_ids(metadata);
+
+        let refs = snapshot_refs(metadata)?.into_iter().fold(
+            HashMap::new(),
+            |mut refs: HashMap<i64, Vec<String>>, (name, reference)| {
+                refs.entry(reference.snapshot_id).or_default().push(name);
+                refs
+            },
+        );
+
+        Ok(Self {
+            metadata,
+            child_counts,
+            main_line,
+            cycles: cycle_snapshot_ids(metadata),
+            refs,
+        })
+    }
+
+    fn lineage_info(&self, snapshot: &Snapshot) -> LineageInfo {
+        let id = snapshot.snapshot_id();
+        LineageInfo {
+            snapshot_id: id,
+            parent_snapshot_id: snapshot.parent_snapshot_id(),
+            timestamp_ms: snapshot.timestamp_ms(),
+            operation: snapshot.summary().operation.clone(),
+            parent_expired: snapshot
+                .parent_snapshot_id()
+                .is_some_and(|parent_id| self.metadata.snapshot_by_id(parent_id).is_none()),
+            main_line: self.main_line.contains(&id),
+            branch_point: self.child_counts.get(&id).is_some_and(|count| *count > 1),
+            in_cycle: self.cycles.contains(&id),
+            refs: self.refs.get(&id).cloned().unwrap_or_default(),
+        }
+    }
+}
+
+/// Returns the IDs of the current snapshot and all of its ancestors.
+fn current_ancestor_ids(metadata: &TableMetadata) -> HashSet<i64> {
+    metadata
+        .current_snapshot()
+        .map(|current| {
+            ancestors(metadata, current)
+                .map(|snapshot| snapshot.snapshot_id())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Iterates from a snapshot back through its parents, stopping at the root,
+/// at the first parent that has been expired, or where corrupt metadata loops
+/// back to a snapshot already visited.
+fn ancestors<'a>(
+    metadata: &'a TableMetadata,
+    snapshot: &'a Snapshot,
+) -> impl Iterator<Item = &'a Snapshot> + 'a {
+    let mut visited = HashSet::new();
+    std::iter::successors(Some(snapshot), move |snapshot| {
+        snapshot
+            .parent_snapshot_id()
+            .and_then(|parent_id| metadata.snapshot_by_id(parent_id))
+            .map(|parent| parent.as_ref())
+    })
+    .take_while(move |snapshot| visited.insert(snapshot.snapshot_id()))
+}
+
+/// Returns the IDs of snapshots whose parents loop back to themselves.
+///
+/// Valid metadata never has such cycles, but corrupt metadata can.
+fn cycle_snapshot_ids(metadata: &TableMetadata) -> HashSet<i64> {
+    let mut cycles = HashSet::new();
+    let mut checked = HashSet::new();
+    for snapshot in metadata.snapshots() {
+        let mut path = Vec::new();
+        let mut id = Some(snapshot.snapshot_id());
+        while let Some(current) = id {
+            if checked.contains(&current) {
+                break;
+            }
+            if let Some(start) = path.iter().position(|&visited| visited == current) {
+                cycles.extend(&path[start..]);
+                break;
+            }
+            path.push(current);
+            id = metadata
+                .snapshot_by_id(current)
+                .and_then(|snapshot| snapshot.parent_snapshot_id());
+        }
+        checked.extend(path);
+    }
+    cycles
+}
+
+/// An entry in the snapshot log, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct HistoryInfo {
+    timestamp_ms: i64,
+    snapshot_id: i64,
+    is_current_ancestor: bool,
+}
+
+/// An entry in the metadata log, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct MetadataLogInfo {
+    timestamp_ms: i64,
+    metadata_file: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exists: Option<bool>,
+}
+
+/// A named branch or tag, for display.
+#[derive(Debug, Serialize)]
+struct RefInfo {
+    name: String,
+    #[serde(flatten)]
+    reference: SnapshotReference,
+}
+
+/// A partition spec resolved against the table schema, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionSpecInfo {
+    spec_id: i32,
+    default: bool,
+    fields: Vec<PartitionFieldInfo>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct PartitionFieldInfo {
+    field_id: i32,
+    name: String,
+    source_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_name: Option<String>,
+    transform: Transform,
+}
+
+impl PartitionSpecInfo {
+    fn from_spec(spec: &PartitionSpec, metadata: &TableMetadata) -> Self {
+        Self {
+            spec_id: spec.spec_id(),
+            default: spec.spec_id() == metadata.default_partition_spec_id(),
+            fields: spec
+                .fields()
+                .iter()
+                .map(|field| PartitionFieldInfo {
+                    field_id: field.field_id,
+                    name: field.name.clone(),
+                    source_id: field.source_id,
+                    source_name: column_name(metadata, field.source_id),
+                    transform: field.transform,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A sort order resolved against the table schema, for display.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct SortOrderInfo {
+    order_id: i64,
+    default: bool,
+    fields: Vec<SortFieldInfo>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct SortFieldInfo {
+    source_id: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_name: Option<String>,
+    transform: Transform,
+    direction: SortDirection,
+    null_order: NullOrder,
+}
+
+impl SortOrderInfo {
+    fn from_sort_order(sort_order: &SortOrder, metadata: &TableMetadata) -> Self {
+        Self {
+            order_id: sort_order.order_id,
+            default: sort_order.order_id == metadata.default_sort_order_id(),
+            fields: sort_order
+                .fields
+                .iter()
+                .map(|field| SortFieldInfo {
+                    source_id: field.source_id,
+                    source_name: column_name(metadata, field.source_id),
+                    transform: field.transform,
+                    direction: field.direction,
+                    null_order: field.null_order,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Resolves a field ID to its (dotted) column name.
+///
+/// Looks in the current schema first, falling back to older schemas so that
+/// columns which have since been dropped still get a name.
+fn column_name(metadata: &TableMetadata, field_id: i32) -> Option<String> {
+    std::iter::once(metadata.current_schema())
+        .chain(metadata.schemas_iter())
+        .find_map(|schema| schema.name_by_field_id(field_id))
+        .map(String::from)
+}
+
 /// Load a Table from a metadata file location
 #[instrument(skip(file_io))]
 pub async fn load_table(file_io: &FileIO, location: &str) -> Result<Table> {
@@ -82,11 +626,57 @@ pub async fn handle_table_command<W: Write>(
         TableCommands::Metadata => handle_metadata(table.metadata(), output),
         TableCommands::Schemas => handle_schemas(table.metadata(), output).await,
         TableCommands::Schema { schema_id } => handle_schema(table.metadata(), &schema_id, output),
+        TableCommands::PartitionSpecs => handle_partition_specs(table.metadata(), output).await,
+        TableCommands::PartitionSpec { spec_id } => {
+            handle_partition_spec(table.metadata(), &spec_id, output)
+        }
+        TableCommands::SortOrders => handle_sort_orders(table.metadata(), output).await,
+        TableCommands::SortOrder { order_id } => {
+            handle_sort_order(table.metadata(), &order_id, output)
+        }
         TableCommands::Snapshots => handle_snapshots(table.metadata(), output).await,
+        TableCommands::Refs => handle_refs(table.metadata(), output).await,
+        TableCommands::Lineage => handle_lineage(table.metadata(), output).await,
+        TableCommands::History => handle_history(table.metadata(), output).await,
+        TableCommands::MetadataLog { verify } => handle_metadata_log(table, verify, output).await,
+        TableCommands::Health { target_file_size } => {
+            handle_health(table, target_file_size, output).await
+        }
+        TableCommands::Orphans { older_than } => {
+            let older_than_ms = parse_timestamp(&older_than, Utc::now())?;
+            handle_orphans(table, older_than_ms, s3_client, output).await
+        }
+        TableCommands::Verify {
+            all_snapshots,
+            refs_only,
+        } => {
+            let scope = match (all_snapshots, refs_only) {
+                (true, _) => VerifyScope::All,
+                (_, true) => VerifyScope::Refs,
+                _ => VerifyScope::Current,
+            };
+            handle_verify(table, scope, s3_client, output).await
+        }
+        TableCommands::Diff { from, to } => {
+            let from = resolve_snapshot(table.metadata(), &from)?;
+            let to = resolve_snapshot(table.metadata(), &to)?;
+            handle_diff(table, from, to, output).await
+        }
         TableCommands::Snapshot {
             snapshot_id,
+            timestamp,
             command,
-        } => handle_snapshot(table, &snapshot_id, command, s3_client, output).await,
+        } => {
+            handle_snapshot(
+                table,
+                &snapshot_id,
+                timestamp.as_deref(),
+                command,
+                s3_client,
+                output,
+            )
+            .await
+        }
     }
 }
 
@@ -125,165 +715,658 @@ fn handle_schema<W: Write>(
     output.display_object(schema)
 }
 
-async fn handle_snapshots<W: Write>(
+async fn handle_partition_specs<W: Write>(
     metadata: &TableMetadata,
     output: &mut TerminalOutput<W>,
 ) -> Result<()> {
-    let snapshots_stream = stream::iter(
-        metadata
-            .snapshots()
-            .map(|s| Ok(SnapshotInfo::from_snapshot(s))),
+    // Specs are stored in a map, so sort them to show partition evolution in order
+    let mut specs: Vec<_> = metadata.partition_specs_iter().collect();
+    specs.sort_by_key(|spec| spec.spec_id());
+    let specs_stream = stream::iter(
+        specs
+            .into_iter()
+            .map(|spec| Ok(PartitionSpecInfo::from_spec(spec, metadata))),
     );
-    output.display_stream(snapshots_stream).await
+    output.display_stream(specs_stream).await
 }
 
-async fn handle_snapshot<W: Write>(
-    table: &Table,
-    snapshot_id: &str,
-    command: SnapshotCmd,
-    s3_client: Option<&aws_sdk_s3::Client>,
+fn handle_partition_spec<W: Write>(
+    metadata: &TableMetadata,
+    spec_id: &str,
     output: &mut TerminalOutput<W>,
 ) -> Result<()> {
-    let metadata = table.metadata();
-
-    let id = if snapshot_id == "current" {
-        metadata
-            .current_snapshot_id()
-            .ok_or_else(|| anyhow::anyhow!("Table has no current snapshot"))?
+    let id = if spec_id == "current" {
+        metadata.default_partition_spec_id()
     } else {
-        snapshot_id
-            .parse::<i64>()
-            .context("Snapshot ID must be an integer")?
+        spec_id
+            .parse::<i32>()
+            .context("Partition spec ID must be an integer")?
     };
 
-    let snapshot = metadata
-        .snapshots()
-        .find(|s| s.snapshot_id() == id)
-        .ok_or_else(|| anyhow::anyhow!("Snapshot {} not found", id))?;
+    let spec = metadata
+        .partition_spec_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Partition spec {} not found", id))?;
 
-    match command {
-        SnapshotCmd::Info => output.display_object(&SnapshotInfo::from_snapshot(snapshot)),
-        SnapshotCmd::Files { verify } => {
-            handle_snapshot_files(table, snapshot, verify, s3_client, output).await
-        }
-    }
+    output.display_object(&PartitionSpecInfo::from_spec(spec, metadata))
 }
 
-/// Derives the data file prefix for a table.
-fn data_file_prefix(metadata: &TableMetadata) -> Result<String> {
-    use iceberg::writer::file_writer::location_generator::{
-        DefaultLocationGenerator, LocationGenerator,
-    };
-    let generator = DefaultLocationGenerator::new(metadata.clone())?;
-    Ok(generator.generate_location(None, ""))
+async fn handle_sort_orders<W: Write>(
+    metadata: &TableMetadata,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let mut sort_orders: Vec<_> = metadata.sort_orders_iter().collect();
+    sort_orders.sort_by_key(|sort_order| sort_order.order_id);
+    let sort_orders_stream = stream::iter(
+        sort_orders
+            .into_iter()
+            .map(|sort_order| Ok(SortOrderInfo::from_sort_order(sort_order, metadata))),
+    );
+    output.display_stream(sort_orders_stream).await
 }
 
-async fn handle_snapshot_files<W: Write>(
-    table: &Table,
-    snapshot: &iceberg::spec::Snapshot,
-    verify: bool,
-    s3_client: Option<&aws_sdk_s3::Client>,
+fn handle_sort_order<W: Write>(
+    metadata: &TableMetadata,
+    order_id: &str,
     output: &mut TerminalOutput<W>,
 ) -> Result<()> {
-    let existence_checker: Option<Box<dyn FileExistenceChecker>> = if verify {
-        let prefix = data_file_prefix(table.metadata())?;
-        Some(create_existence_checker(table.file_io().clone(), &prefix, s3_client).await?)
+    let id = if order_id == "current" {
+        metadata.default_sort_order_id()
     } else {
-        None
+        order_id
+            .parse::<i64>()
+            .context("Sort order ID must be an integer")?
     };
 
-    let stream = iterate_files(table, snapshot, existence_checker.as_deref());
+    let sort_order = metadata
+        .sort_order_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Sort order {} not found", id))?;
 
-    // Count missing files while displaying the stream
-    let missing_count = std::cell::Cell::new(0usize);
-    let counting_stream = stream.inspect(|result| {
-        if let Ok(record) = result
-            && record.exists == Some(false)
-        {
-            missing_count.set(missing_count.get() + 1);
-        }
-    });
+    output.display_object(&SortOrderInfo::from_sort_order(sort_order, metadata))
+}
 
-    output.display_stream(counting_stream).await?;
+async fn handle_snapshots<W: Write>(
+    metadata: &TableMetadata,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let snapshots_stream = stream::iter(
+        metadata
+            .snapshots()
+            .map(|s| Ok(SnapshotInfo::from_snapshot(s))),
+    );
+    output.display_stream(snapshots_stream).await
+}
 
-    // If verifying and any files are missing, return a Failed error
-    if verify && missing_count.get() > 0 {
-        return Err(anyhow::Error::new(ExpectedError::Failed(format!(
-            "table is corrupt - {} file(s) missing",
-            missing_count.get()
-        ))));
-    }
+async fn handle_refs<W: Write>(
+    metadata: &TableMetadata,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let refs_stream = stream::iter(
+        snapshot_refs(metadata)?
+            .into_iter()
+            .map(|(name, reference)| Ok(RefInfo { name, reference })),
+    );
+    output.display_stream(refs_stream).await
+}
 
-    Ok(())
+async fn handle_lineage<W: Write>(
+    metadata: &TableMetadata,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let graph = SnapshotGraph::new(metadata)?;
+    let mut snapshots: Vec<_> = metadata.snapshots().collect();
+    snapshots.sort_by_key(|snapshot| (snapshot.timestamp_ms(), snapshot.snapshot_id()));
+    let lineage_stream = stream::iter(
+        snapshots
+            .into_iter()
+            .map(|snapshot| Ok(graph.lineage_info(snapshot))),
+    );
+    output.display_stream(lineage_stream).await
 }
 
-#[instrument(skip(table, existence_checker))]
-fn iterate_files<'a>(
-    table: &'a Table,
-    snapshot: &'a iceberg::spec::Snapshot,
-    existence_checker: Option<&'a dyn FileExistenceChecker>,
-) -> impl Stream<Item = Result<FileRecord>> + 'a {
-    try_stream! {
-        let file_io = table.file_io();
-        let format_version = table.metadata().format_version();
-        let verifying = existence_checker.is_some();
-        let implicitly_exists = if verifying { Some(true) } else { None };
-        let manifest_list_location = snapshot.manifest_list();
-        yield FileRecord {
-            r#type: FileType::ManifestList,
-            path: manifest_list_location.to_string(),
-            exists: implicitly_exists,
+async fn handle_history<W: Write>(
+    metadata: &TableMetadata,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let current_ancestors = current_ancestor_ids(metadata);
+    let history_stream = stream::iter(metadata.history().iter().map(|entry| {
+        Ok(HistoryInfo {
+            timestamp_ms: entry.timestamp_ms(),
+            snapshot_id: entry.snapshot_id,
+            is_current_ancestor: current_ancestors.contains(&entry.snapshot_id),
+        })
+    }));
+    output.display_stream(history_stream).await
+}
+
+async fn handle_metadata_log<W: Write>(
+    table: &Table,
+    verify: bool,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    // The metadata directory also holds every manifest, so listing it to
+    // preload existence is usually far more work than checking each entry.
+    let existence_checker = verify.then(|| FileIOExistenceChecker::new(table.file_io().clone()));
+
+    let metadata_log_stream = try_stream! {
+        for entry in table.metadata().metadata_log() {
+            let exists = match &existence_checker {
+                Some(checker) => Some(checker.exists(&entry.metadata_file).await?),
+                None => None,
+            };
+            yield MetadataLogInfo {
+                timestamp_ms: entry.timestamp_ms,
+                metadata_file: entry.metadata_file.clone(),
+                exists,
+            };
+        }
+    };
+    output.display_stream(metadata_log_stream).await
+}
+
+async fn handle_ancestry<W: Write>(
+    metadata: &TableMetadata,
+    snapshot: &Snapshot,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let graph = SnapshotGraph::new(metadata)?;
+    let ancestry_stream = stream::iter(
+        ancestors(metadata, snapshot).map(|ancestor| Ok(graph.lineage_info(ancestor))),
+    );
+    output.display_stream(ancestry_stream).await
+}
+
+/// Returns the table's branches and tags, keyed by name.
+///
+/// `TableMetadata` keeps its refs map private, so we recover it from the
+/// serialized metadata instead, skipping over every other field. Prefer
+/// `TableMetadata::snapshot_for_ref` when only a ref's snapshot is needed.
+pub(crate) fn snapshot_refs(
+    metadata: &TableMetadata,
+) -> Result<BTreeMap<String, SnapshotReference>> {
+    #[derive(Deserialize)]
+    struct Refs {
+        #[serde(default)]
+        refs: Option<BTreeMap<String, SnapshotReference>>,
+    }
+
+    let json = serde_json::to_vec(metadata)?;
+    let refs: Refs = serde_json::from_slice(&json)?;
+    Ok(refs.refs.unwrap_or_default())
+}
+
+/// Resolves a snapshot selector to a snapshot.
+///
+/// A selector is a snapshot ID, "current", "branch:<name>", "tag:<name>",
+/// or a bare branch or tag name. Refs named like a snapshot ID, "current" or
+/// "as-of" need the "branch:" or "tag:" prefix.
+pub(crate) fn resolve_snapshot<'a>(
+    metadata: &'a TableMetadata,
+    selector: &str,
+) -> Result<&'a SnapshotRef> {
+    let id = if selector == "current" {
+        metadata
+            .current_snapshot_id()
+            .ok_or_else(|| anyhow::anyhow!("Table has no current snapshot"))?
+    } else if let Some(name) = selector.strip_prefix("branch:") {
+        return ref_snapshot(metadata, name, true);
+    } else if let Some(name) = selector.strip_prefix("tag:") {
+        return ref_snapshot(metadata, name, false);
+    } else if let Ok(id) = selector.parse::<i64>() {
+        id
+    } else {
+        return metadata.snapshot_for_ref(selector).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Snapshot ID must be an integer, \"current\" or a ref name - no ref named '{}'",
+                selector
+            )
+        });
+    };
+
+    metadata
+        .snapshot_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Snapshot {} not found", id))
+}
+
+/// Finds the snapshot that was current at the given time, using the snapshot log.
+fn snapshot_as_of(metadata: &TableMetadata, timestamp_ms: i64) -> Result<&SnapshotRef> {
+    let history = metadata.history();
+
+    let entry = history
+        .iter()
+        .rev()
+        .find(|entry| entry.timestamp_ms() <= timestamp_ms)
+        .ok_or_else(|| match history.first() {
+            Some(first) => anyhow::anyhow!(
+                "{} predates the table's history, which starts at {}",
+                format_timestamp_ms(timestamp_ms),
+                format_timestamp_ms(first.timestamp_ms())
+            ),
+            None => anyhow::anyhow!("Table has no snapshot history"),
+        })?;
+
+    metadata.snapshot_by_id(entry.snapshot_id).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Snapshot {} (current as of {}) has expired",
+            entry.snapshot_id,
+            format_timestamp_ms(timestamp_ms)
+        )
+    })
+}
+
+/// Parses a timestamp into epoch milliseconds.
+///
+/// Accepts RFC 3339 (e.g. "2026-10-01T12:00:00Z"), epoch milliseconds, or a
+/// time relative to `now` such as "-30m", "-2h" or "-7d".
+fn parse_timestamp(timestamp: &str, now: DateTime<Utc>) -> Result<i64> {
+    if let Some(relative) = timestamp.strip_prefix('-') {
+        let unit_pos = relative
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(relative.len());
+        let (amount, unit) = relative.split_at(unit_pos);
+        let unit_seconds = match unit {
+            "s" => 1,
+            "m" => 60,
+            "h" => 60 * 60,
+            "d" => 24 * 60 * 60,
+            "w" => 7 * 24 * 60 * 60,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Invalid relative time '{}': expected a unit of s, m, h, d or w",
+                    timestamp
+                ));
+            }
+        };
+        let offset = amount
+            .parse::<i64>()
+            .ok()
+            .and_then(|amount| amount.checked_mul(unit_seconds))
+            .and_then(TimeDelta::try_seconds)
+            .ok_or_else(|| anyhow::anyhow!("Invalid relative time '{}'", timestamp))?;
+        return Ok((now - offset).timestamp_millis());
+    }
+
+    timestamp.parse::<i64>().or_else(|_| {
+        DateTime::parse_from_rfc3339(timestamp)
+            .map(|datetime| datetime.timestamp_millis())
+            .with_context(|| {
+                format!(
+                    "Invalid timestamp '{}': expected RFC 3339 (e.g. 2026-10-01T12:00:00Z), \
+                     epoch milliseconds, or a relative time (e.g. -2h)",
+                    timestamp
+                )
+            })
+    })
+}
+
+/// Formats epoch milliseconds as an RFC 3339 timestamp.
+fn format_timestamp_ms(timestamp_ms: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .unwrap_or_else(|| timestamp_ms.to_string())
+}
+
+/// Looks up the snapshot of a named branch (or tag, if `branch` is false).
+fn ref_snapshot<'a>(
+    metadata: &'a TableMetadata,
+    name: &str,
+    branch: bool,
+) -> Result<&'a SnapshotRef> {
+    let kind = if branch { "branch" } else { "tag" };
+    let not_found = || anyhow::anyhow!("No {} named '{}'", kind, name);
+    let snapshot = metadata.snapshot_for_ref(name).ok_or_else(not_found)?;
+    // Only the serialized refs say whether a ref is a branch or a tag
+    let is_branch = snapshot_refs(metadata)?
+        .get(name)
+        .is_some_and(|reference| reference.is_branch());
+    if is_branch != branch {
+        return Err(not_found());
+    }
+    Ok(snapshot)
+}
+
+async fn handle_snapshot<W: Write>(
+    table: &Table,
+    snapshot_id: &str,
+    timestamp: Option<&str>,
+    command: SnapshotCmd,
+    s3_client: Option<&aws_sdk_s3::Client>,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let metadata = table.metadata();
+
+    let snapshot = match (snapshot_id, timestamp) {
+        ("as-of", Some(timestamp)) => {
+            snapshot_as_of(metadata, parse_timestamp(timestamp, Utc::now())?)?
+        }
+        ("as-of", None) => return Err(anyhow::anyhow!("\"as-of\" requires a timestamp")),
+        (_, Some(timestamp)) => {
+            return Err(anyhow::anyhow!(
+                "Unexpected argument '{}' (a timestamp is only valid after \"as-of\")",
+                timestamp
+            ));
+        }
+        (selector, None) => resolve_snapshot(metadata, selector)?,
+    };
+
+    match command {
+        SnapshotCmd::Info => output.display_object(&SnapshotInfo::from_snapshot(snapshot)),
+        SnapshotCmd::Ancestry => handle_ancestry(metadata, snapshot, output).await,
+        SnapshotCmd::Manifests => handle_snapshot_manifests(table, snapshot, output).await,
+        SnapshotCmd::Partitions => handle_partitions(table, snapshot, output).await,
+        SnapshotCmd::Files(args) => {
+            handle_snapshot_files(table, snapshot, args, s3_client, output).await
+        }
+    }
+}
+
+async fn handle_snapshot_manifests<W: Write>(
+    table: &Table,
+    snapshot: &Snapshot,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let manifest_list = load_manifest_list(table, snapshot).await?;
+    let manifests_stream = stream::iter(manifest_list.entries().iter().map(|manifest_file| {
+        Ok(ManifestInfo::from_manifest_file(
+            manifest_file,
+            table.metadata(),
+        ))
+    }));
+    output.display_stream(manifests_stream).await
+}
+
+/// Derives the data file prefix for a table.
+pub(crate) fn data_file_prefix(metadata: &TableMetadata) -> Result<String> {
+    use iceberg::writer::file_writer::location_generator::{
+        DefaultLocationGenerator, LocationGenerator,
+    };
+    let generator = DefaultLocationGenerator::new(metadata.clone())?;
+    Ok(generator.generate_location(None, ""))
+}
+
+/// Estimates how many files a snapshot's manifests list, from the manifest list's counts.
+fn expected_file_count(manifest_list: &ManifestList) -> u64 {
+    manifest_list
+        .entries()
+        .iter()
+        .map(|manifest_file| {
+            u64::from(manifest_file.added_files_count.unwrap_or_default())
+                + u64::from(manifest_file.existing_files_count.unwrap_or_default())
+        })
+        .sum()
+}
+
+/// How many data file footers `--deep` reads at once
+const DEEP_VERIFY_CONCURRENCY: usize = 8;
+
+async fn handle_snapshot_files<W: Write>(
+    table: &Table,
+    snapshot: &iceberg::spec::Snapshot,
+    args: FilesArgs,
+    s3_client: Option<&aws_sdk_s3::Client>,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let started = Instant::now();
+    let verify = args.verify || args.verify_size || args.deep || args.report.is_some();
+    // Loaded once here to size the existence checker; a failure is reported by the stream
+    let manifest_list = load_listed_manifest_list(table, snapshot, verify).await;
+    let existence_checker: Option<Box<dyn FileExistenceChecker>> = if verify {
+        let prefix = data_file_prefix(table.metadata())?;
+        let expected_files = match &manifest_list {
+            Ok(Some(manifest_list)) => expected_file_count(manifest_list),
+            _ => 0,
         };
+        Some(
+            create_existence_checker(table.file_io().clone(), &prefix, s3_client, expected_files)
+                .await?,
+        )
+    } else {
+        None
+    };
+
+    let stream = iterate_listed_files(
+        table,
+        snapshot,
+        manifest_list,
+        args.content,
+        existence_checker.as_deref(),
+        args.verify_size,
+    )
+    .map_ok(|mut record| {
+        if args.details
+            && let (Some(entry), Some(partition_spec_id)) =
+                (&record.entry, record.partition_spec_id)
+        {
+            record.details = Some(FileDetails::from_entry(
+                entry,
+                partition_spec_id,
+                table.metadata(),
+            ));
+        }
+        record
+    })
+    .map(|result| async move {
+        let mut record = result?;
+        if args.deep
+            && record.r#type == FileType::Data
+            && record.exists == Some(true)
+            && let Some(entry) = &record.entry
+        {
+            record.failures = check_data_file(table.file_io(), entry, table.metadata()).await?;
+        }
+        Ok(record)
+    })
+    .buffered(DEEP_VERIFY_CONCURRENCY);
+
+    // Tally missing, wrongly sized and corrupt files while displaying the stream
+    let report = std::cell::RefCell::new(VerificationReport::new(
+        snapshot.snapshot_id(),
+        args.verify_size,
+        args.deep,
+    ));
+    let counting_stream = stream.inspect(|result| {
+        if let Ok(record) = result
+            && verify
+        {
+            report.borrow_mut().add(record);
+        }
+    });
 
-        let manifest_list_bytes = fetch_bytes(file_io, manifest_list_location).await?;
-        let manifest_list = ManifestList::parse_with_version(&manifest_list_bytes, format_version)
-            .context("Failed to parse manifest list")?;
+    let displayed = output.display_stream(counting_stream).await;
 
-        let tasks = manifest_list.entries().iter().map(|manifest_file| {
-            let manifest_location = manifest_file.manifest_path.clone();
-            let file_io = file_io.clone();
-            async move {
-                let bytes_result = fetch_bytes(&file_io, &manifest_location).await;
-                (manifest_location, bytes_result)
+    // Write the report even if listing failed part way, noting why
+    let mut report = report.into_inner();
+    report.finish(
+        started.elapsed(),
+        existence_checker.as_ref().map(|checker| checker.strategy()),
+    );
+    if let Some(path) = &args.report {
+        match &displayed {
+            Ok(()) => report.write(path)?,
+            // Listing's error is the one to return, so only mention the report's
+            Err(e) => {
+                report.fail(e);
+                if let Err(write_error) = report.write(path) {
+                    eprintln!("WARNING: {write_error:#}");
+                }
             }
-        });
+        }
+    }
+    displayed?;
+
+    // If verifying and any files are missing, truncated or corrupt, return a Failed error
+    let mut problems = Vec::new();
+    if report.missing_count() > 0 {
+        problems.push(format!("{} file(s) missing", report.missing_count()));
+    }
+    if report.size_mismatch_count() > 0 {
+        problems.push(format!(
+            "{} file(s) with unexpected size",
+            report.size_mismatch_count()
+        ));
+    }
+    if report.deep_failure_count() > 0 {
+        problems.push(format!(
+            "{} file(s) failed deep verification",
+            report.deep_failure_count()
+        ));
+    }
+    if verify && !problems.is_empty() {
+        return Err(anyhow::Error::new(ExpectedError::Failed(format!(
+            "table is corrupt - {}",
+            problems.join(", ")
+        ))));
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(table, existence_checker))]
+pub(crate) fn iterate_files<'a>(
+    table: &'a Table,
+    snapshot: &'a iceberg::spec::Snapshot,
+    content: ContentFilter,
+    existence_checker: Option<&'a dyn FileExistenceChecker>,
+    check_sizes: bool,
+) -> impl Stream<Item = Result<FileRecord>> + 'a {
+    try_stream! {
+        let manifest_list =
+            load_listed_manifest_list(table, snapshot, existence_checker.is_some()).await;
+        let files = iterate_listed_files(
+            table,
+            snapshot,
+            manifest_list,
+            content,
+            existence_checker,
+            check_sizes,
+        );
+        for await record in files {
+            yield record?;
+        }
+    }
+}
+
+/// Loads a snapshot's manifest list for listing its files.
+///
+/// When verifying, a missing manifest list is `None`, to be reported rather than
+/// fatal; anything else that stops it loading is still an error.
+async fn load_listed_manifest_list(
+    table: &Table,
+    snapshot: &Snapshot,
+    verifying: bool,
+) -> Result<Option<ManifestList>> {
+    match load_manifest_list(table, snapshot).await {
+        Ok(manifest_list) => Ok(Some(manifest_list)),
+        Err(_) if verifying && !table.file_io().exists(snapshot.manifest_list()).await? => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Lists the files under an already loaded manifest list, as `iterate_files` does.
+fn iterate_listed_files<'a>(
+    table: &'a Table,
+    snapshot: &'a iceberg::spec::Snapshot,
+    manifest_list: Result<Option<ManifestList>>,
+    content: ContentFilter,
+    existence_checker: Option<&'a dyn FileExistenceChecker>,
+    check_sizes: bool,
+) -> impl Stream<Item = Result<FileRecord>> + 'a {
+    try_stream! {
+        let file_io = table.file_io();
+        let verifying = existence_checker.is_some();
+        let implicitly_exists = if verifying { Some(true) } else { None };
+        let manifest_list_location = snapshot.manifest_list();
+        let manifest_list = manifest_list?;
+
+        yield FileRecord::new(
+            FileType::ManifestList,
+            manifest_list_location.to_string(),
+            if manifest_list.is_some() { implicitly_exists } else { Some(false) },
+        );
+        let manifest_files = manifest_list
+            .as_ref()
+            .map(|manifest_list| manifest_list.entries())
+            .unwrap_or_default();
+
+        // Load via the manifest list entry so that entries inherit sequence numbers
+        let tasks = manifest_files
+            .iter()
+            .filter(|manifest_file| content.includes(manifest_file.content.into()))
+            .map(|manifest_file| {
+                let file_io = file_io.clone();
+                async move { (manifest_file, manifest_file.load_manifest(&file_io).await) }
+            });
 
         let mut stream = stream::iter(tasks).buffered(7);
 
-        while let Some((manifest_location, bytes_result)) = stream.next().await {
+        // When verifying, a missing manifest is reported rather than fatal
+        while let Some((manifest_file, manifest_result)) = stream.next().await {
+            let manifest_path = &manifest_file.manifest_path;
+            let manifest = match manifest_result {
+                Ok(manifest) => Some(manifest),
+                Err(_) if verifying && !file_io.exists(manifest_path).await? => None,
+                Err(e) => Err(e)
+                    .with_context(|| format!("Failed to load manifest {}", manifest_path))?,
+            };
             yield FileRecord {
-                r#type: FileType::Manifest,
-                path: manifest_location.clone(),
-                exists: implicitly_exists,
+                manifest_file: Some(manifest_file.clone()),
+                ..FileRecord::new(
+                    FileType::Manifest,
+                    manifest_path.clone(),
+                    if manifest.is_some() { implicitly_exists } else { Some(false) },
+                )
+            };
+            let Some(manifest) = manifest else {
+                continue;
             };
 
-            let manifest_bytes = bytes_result?;
-            let manifest = Manifest::parse_avro(manifest_bytes.as_slice())
-                .context("Failed to parse manifest")?;
-
-            // Collect data file paths (excluding deleted entries)
-            let data_files: Vec<String> = manifest
+            // Collect data file entries (excluding deleted entries)
+            let entries: Vec<ManifestEntryRef> = manifest
                 .entries()
                 .iter()
                 .filter(|entry| entry.status() != iceberg::spec::ManifestStatus::Deleted)
-                .map(|entry| entry.data_file().file_path().to_string())
+                .cloned()
                 .collect();
 
-            for path in data_files {
-                let exists = match existence_checker {
-                    Some(checker) => Some(checker.exists(&path).await?),
-                    None => None,
+            for entry in entries {
+                let path = entry.file_path().to_string();
+                let (exists, size_mismatch) = match existence_checker {
+                    Some(checker) if check_sizes => {
+                        let expected = entry.file_size_in_bytes();
+                        let actual = checker.size(&path).await?;
+                        let size_mismatch = actual
+                            .filter(|actual| *actual != expected)
+                            .map(|actual| SizeMismatch { expected, actual });
+                        (Some(actual.is_some()), size_mismatch)
+                    }
+                    Some(checker) => (Some(checker.exists(&path).await?), None),
+                    None => (None, None),
                 };
+                let data_file = entry.data_file();
                 yield FileRecord {
-                    r#type: FileType::Data,
+                    r#type: FileType::of(data_file),
                     path,
                     exists,
+                    size_mismatch,
+                    failures: Vec::new(),
+                    referenced_data_file: data_file.referenced_data_file(),
+                    equality_ids: data_file.equality_ids(),
+                    details: None,
+                    entry: Some(entry),
+                    partition_spec_id: Some(manifest_file.partition_spec_id),
+                    manifest_file: None,
                 };
             }
         }
     }
 }
 
+/// Fetches and parses a snapshot's manifest list.
+pub(crate) async fn load_manifest_list(table: &Table, snapshot: &Snapshot) -> Result<ManifestList> {
+    let bytes = fetch_bytes(table.file_io(), snapshot.manifest_list()).await?;
+    ManifestList::parse_with_version(&bytes, table.metadata().format_version())
+        .context("Failed to parse manifest list")
+}
+
 async fn fetch_bytes(file_io: &FileIO, location: &str) -> Result<Vec<u8>> {
     let input_file = file_io.new_input(location)?;
     let bytes = input_file.read().await?;
@@ -293,75 +1376,7 @@ async fn fetch_bytes(file_io: &FileIO, location: &str) -> Result<Vec<u8>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    async fn create_memory_file_io(files: Vec<(&str, &str)>) -> FileIO {
-        let file_io = FileIO::new_with_memory();
-
-        for (path, content) in files {
-            let output_file = file_io.new_output(path).unwrap();
-            output_file
-                .write(bytes::Bytes::from(content.to_string()))
-                .await
-                .unwrap();
-        }
-
-        file_io
-    }
-
-    /// Returns metadata for an empty Iceberg table (no snapshots)
-    fn empty_metadata() -> serde_json::Value {
-        serde_json::json!({
-            "format-version": 2,
-            "table-uuid": "9c2c0c2c-9c2c-9c2c-9c2c-9c2c0c2c0c2c",
-            "location": "s3://bucket/table",
-            "last-sequence-number": 1,
-            "last-updated-ms": 1600000000000_i64,
-            "last-column-id": 1,
-            "current-schema-id": 0,
-            "schemas": [
-                {
-                    "type": "struct",
-                    "schema-id": 0,
-                    "fields": [
-                        {
-                            "id": 1,
-                            "name": "id",
-                            "required": true,
-                            "type": "int"
-                        }
-                    ]
-                }
-            ],
-            "default-spec-id": 0,
-            "partition-specs": [{"spec-id": 0, "fields": []}],
-            "last-partition-id": 999,
-            "default-sort-order-id": 0,
-            "sort-orders": [{"order-id": 0, "fields": []}],
-            "properties": {},
-            "refs": {},
-            "snapshots": [],
-            "snapshot-log": [],
-            "metadata-log": []
-        })
-    }
-
-    /// Returns metadata with a single snapshot added
-    fn metadata_with_snapshot(snapshot_id: i64, manifest_list: &str) -> serde_json::Value {
-        let mut metadata = empty_metadata();
-
-        metadata["current-snapshot-id"] = serde_json::json!(snapshot_id);
-        metadata["snapshots"] = serde_json::json!([
-            {
-                "snapshot-id": snapshot_id,
-                "sequence-number": 1,
-                "timestamp-ms": 1600000000000_i64,
-                "manifest-list": manifest_list,
-                "summary": { "operation": "append" },
-                "schema-id": 0
-            }
-        ]);
-
-        metadata
-    }
+    use crate::test_support::*;
 
     /// Returns minimal metadata with one snapshot (for backwards compatibility)
     fn minimal_metadata() -> String {
@@ -416,6 +1431,7 @@ mod tests {
             &table,
             TableCommands::Snapshot {
                 snapshot_id: "current".to_string(),
+                timestamp: None,
                 command: SnapshotCmd::Info,
             },
             &mut output,
@@ -568,32 +1584,55 @@ mod tests {
         Ok(())
     }
 
+    /// Returns minimal metadata with a second, partitioned spec as the default
+    fn partitioned_metadata() -> String {
+        let mut metadata = metadata_with_snapshot(123, "s3://bucket/table/snap-123.avro");
+
+        metadata["default-spec-id"] = serde_json::json!(1);
+        metadata["last-partition-id"] = serde_json::json!(1000);
+        metadata["partition-specs"] = serde_json::json!([
+            {"spec-id": 0, "fields": []},
+            {
+                "spec-id": 1,
+                "fields": [
+                    {"source-id": 1, "field-id": 1000, "name": "id_bucket", "transform": "bucket[16]"}
+                ]
+            }
+        ]);
+
+        serde_json::to_string(&metadata).unwrap()
+    }
+
     #[tokio::test]
-    async fn test_handle_snapshots() -> Result<()> {
-        let metadata_json = minimal_metadata();
+    async fn test_handle_partition_specs() -> Result<()> {
+        let metadata_json = partitioned_metadata();
         let location = "s3://bucket/table/metadata.json";
         let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
         let table = load_table(&file_io, location).await?;
 
         let mut buffer = Vec::new();
         let mut output = TerminalOutput::with_writer(&mut buffer);
-        handle_table_command(&table, TableCommands::Snapshots, &mut output, None).await?;
+        handle_table_command(&table, TableCommands::PartitionSpecs, &mut output, None).await?;
 
-        // Verify JSONL output
         let output_str = String::from_utf8(buffer)?;
-        let lines: Vec<&str> = output_str.lines().collect();
-
-        assert_eq!(lines.len(), 1); // minimal_metadata has 1 snapshot
-
-        let snapshot: serde_json::Value = serde_json::from_str(lines[0])?;
-        assert_eq!(snapshot["snapshot-id"], 123);
+        let specs: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(specs.len(), 2);
+        assert_eq!(specs[0]["default"], false);
+        assert_eq!(specs[1]["spec-id"], 1);
+        assert_eq!(specs[1]["default"], true);
+        assert_eq!(specs[1]["fields"][0]["source-name"], "id");
+        assert_eq!(specs[1]["fields"][0]["transform"], "bucket[16]");
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_handle_snapshot_by_id() -> Result<()> {
-        let metadata_json = minimal_metadata();
+    async fn test_handle_partition_spec_current() -> Result<()> {
+        let metadata_json = partitioned_metadata();
         let location = "s3://bucket/table/metadata.json";
         let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
         let table = load_table(&file_io, location).await?;
@@ -602,9 +1641,8 @@ mod tests {
         let mut output = TerminalOutput::with_writer(&mut buffer);
         handle_table_command(
             &table,
-            TableCommands::Snapshot {
-                snapshot_id: "123".to_string(),
-                command: SnapshotCmd::Info,
+            TableCommands::PartitionSpec {
+                spec_id: "current".to_string(),
             },
             &mut output,
             None,
@@ -612,10 +1650,1304 @@ mod tests {
         .await?;
 
         let output_str = String::from_utf8(buffer)?;
-        let snapshot: serde_json::Value = serde_json::from_str(output_str.trim())?;
+        let spec: serde_json::Value = serde_json::from_str(output_str.trim())?;
 
-        assert_eq!(snapshot["snapshot-id"], 123);
-        assert_eq!(snapshot["manifest-list"], "s3://bucket/table/snap-123.avro");
+        assert_eq!(spec["spec-id"], 1);
+        assert_eq!(spec["default"], true);
+        assert_eq!(spec["fields"][0]["field-id"], 1000);
+        assert_eq!(spec["fields"][0]["name"], "id_bucket");
+        assert_eq!(spec["fields"][0]["source-id"], 1);
+        assert_eq!(spec["fields"][0]["source-name"], "id");
+        assert_eq!(spec["fields"][0]["transform"], "bucket[16]");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_partition_spec_not_found() -> Result<()> {
+        let metadata_json = partitioned_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        let result = handle_table_command(
+            &table,
+            TableCommands::PartitionSpec {
+                spec_id: "7".to_string(),
+            },
+            &mut output,
+            None,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Partition spec 7 not found")
+        );
+
+        Ok(())
+    }
+
+    /// Returns minimal metadata with a second sort order as the default
+    fn sorted_metadata() -> String {
+        let mut metadata = metadata_with_snapshot(123, "s3://bucket/table/snap-123.avro");
+
+        metadata["default-sort-order-id"] = serde_json::json!(1);
+        metadata["sort-orders"] = serde_json::json!([
+            {"order-id": 0, "fields": []},
+            {
+                "order-id": 1,
+                "fields": [
+                    {"source-id": 1, "transform": "identity", "direction": "desc", "null-order": "nulls-last"}
+                ]
+            }
+        ]);
+
+        serde_json::to_string(&metadata).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handle_sort_orders() -> Result<()> {
+        let metadata_json = sorted_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(&table, TableCommands::SortOrders, &mut output, None).await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let sort_orders: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(sort_orders.len(), 2);
+        assert_eq!(sort_orders[0]["order-id"], 0);
+        assert_eq!(sort_orders[0]["default"], false);
+        assert_eq!(sort_orders[1]["order-id"], 1);
+        assert_eq!(sort_orders[1]["default"], true);
+        assert_eq!(sort_orders[1]["fields"][0]["source-name"], "id");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_sort_order_current() -> Result<()> {
+        let metadata_json = sorted_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::SortOrder {
+                order_id: "current".to_string(),
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let sort_order: serde_json::Value = serde_json::from_str(output_str.trim())?;
+
+        assert_eq!(sort_order["order-id"], 1);
+        assert_eq!(sort_order["fields"][0]["source-id"], 1);
+        assert_eq!(sort_order["fields"][0]["source-name"], "id");
+        assert_eq!(sort_order["fields"][0]["transform"], "identity");
+        assert_eq!(sort_order["fields"][0]["direction"], "desc");
+        assert_eq!(sort_order["fields"][0]["null-order"], "nulls-last");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_sort_order_invalid_id() -> Result<()> {
+        let metadata_json = sorted_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        let result = handle_table_command(
+            &table,
+            TableCommands::SortOrder {
+                order_id: "invalid".to_string(),
+            },
+            &mut output,
+            None,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Sort order ID must be an integer")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshots() -> Result<()> {
+        let metadata_json = minimal_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(&table, TableCommands::Snapshots, &mut output, None).await?;
+
+        // Verify JSONL output
+        let output_str = String::from_utf8(buffer)?;
+        let lines: Vec<&str> = output_str.lines().collect();
+
+        assert_eq!(lines.len(), 1); // minimal_metadata has 1 snapshot
+
+        let snapshot: serde_json::Value = serde_json::from_str(lines[0])?;
+        assert_eq!(snapshot["snapshot-id"], 123);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_by_id() -> Result<()> {
+        let metadata_json = minimal_metadata();
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "123".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Info,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let snapshot: serde_json::Value = serde_json::from_str(output_str.trim())?;
+
+        assert_eq!(snapshot["snapshot-id"], 123);
+        assert_eq!(snapshot["manifest-list"], "s3://bucket/table/snap-123.avro");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_refs() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(&table, TableCommands::Refs, &mut output, None).await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let refs: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        // Refs are listed by name
+        assert_eq!(refs.len(), 3);
+        assert_eq!(refs[0]["name"], "audit");
+        assert_eq!(refs[0]["type"], "branch");
+        assert_eq!(refs[0]["snapshot-id"], 123);
+        assert_eq!(refs[0]["min-snapshots-to-keep"], 5);
+        assert_eq!(refs[1]["name"], "main");
+        assert_eq!(refs[1]["snapshot-id"], 456);
+        assert_eq!(refs[2]["name"], "v1");
+        assert_eq!(refs[2]["type"], "tag");
+        assert_eq!(refs[2]["max-ref-age-ms"], 86400000);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_by_ref() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        for (selector, expected_id) in [
+            ("branch:main", 456),
+            ("branch:audit", 123),
+            ("tag:v1", 123),
+            ("audit", 123),
+            ("main", 456),
+        ] {
+            let mut buffer = Vec::new();
+            let mut output = TerminalOutput::with_writer(&mut buffer);
+            handle_table_command(
+                &table,
+                TableCommands::Snapshot {
+                    snapshot_id: selector.to_string(),
+                    timestamp: None,
+                    command: SnapshotCmd::Info,
+                },
+                &mut output,
+                None,
+            )
+            .await?;
+
+            let output_str = String::from_utf8(buffer)?;
+            let snapshot: serde_json::Value = serde_json::from_str(output_str.trim())?;
+            assert_eq!(
+                snapshot["snapshot-id"], expected_id,
+                "selector {}",
+                selector
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_ref_wrong_type() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        let result = handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "branch:v1".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Info,
+            },
+            &mut output,
+            None,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("No branch named 'v1'")
+        );
+
+        Ok(())
+    }
+
+    /// Returns metadata with history plus a side branch off snapshot 123 and
+    /// a snapshot whose parent has been expired
+    fn metadata_with_branches() -> serde_json::Value {
+        let mut metadata = metadata_with_history();
+
+        metadata["last-sequence-number"] = serde_json::json!(4);
+        let snapshots = metadata["snapshots"].as_array_mut().unwrap();
+        snapshots.push(serde_json::json!({
+            "snapshot-id": 789,
+            "parent-snapshot-id": 123,
+            "sequence-number": 3,
+            "timestamp-ms": 1600000150000_i64,
+            "manifest-list": "s3://bucket/table/snap-789.avro",
+            "summary": { "operation": "append" },
+            "schema-id": 0
+        }));
+        snapshots.push(serde_json::json!({
+            "snapshot-id": 790,
+            "parent-snapshot-id": 555,
+            "sequence-number": 4,
+            "timestamp-ms": 1600000160000_i64,
+            "manifest-list": "s3://bucket/table/snap-790.avro",
+            "summary": { "operation": "delete" },
+            "schema-id": 0
+        }));
+
+        metadata
+    }
+
+    #[tokio::test]
+    async fn test_handle_lineage() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_branches())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(&table, TableCommands::Lineage, &mut output, None).await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let lineage: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        // Ordered by timestamp: 123, 456, 789, 790
+        assert_eq!(lineage.len(), 4);
+
+        assert_eq!(lineage[0]["snapshot-id"], 123);
+        assert_eq!(lineage[0]["main-line"], true);
+        assert_eq!(lineage[0]["branch-point"], true);
+        assert_eq!(lineage[0]["refs"], serde_json::json!(["audit", "v1"]));
+
+        assert_eq!(lineage[1]["snapshot-id"], 456);
+        assert_eq!(lineage[1]["main-line"], true);
+        assert_eq!(lineage[1]["branch-point"], false);
+        assert_eq!(lineage[1]["refs"], serde_json::json!(["main"]));
+
+        assert_eq!(lineage[2]["snapshot-id"], 789);
+        assert_eq!(lineage[2]["main-line"], false);
+        assert_eq!(lineage[2]["parent-expired"], false);
+        assert!(lineage[2].get("refs").is_none());
+
+        assert_eq!(lineage[3]["snapshot-id"], 790);
+        assert_eq!(lineage[3]["main-line"], false);
+        assert_eq!(lineage[3]["parent-expired"], true);
+        assert_eq!(lineage[3]["operation"], "delete");
+        assert_eq!(lineage[3]["in-cycle"], false);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_ancestry_parent_cycle() -> Result<()> {
+        // Corrupt metadata: 123 and 789 are each other's parent
+        let mut metadata = metadata_with_branches();
+        metadata["snapshots"][0]["parent-snapshot-id"] = serde_json::json!(789);
+        let metadata_json = serde_json::to_string(&metadata)?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Ancestry,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let ancestry: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        // The walk stops when it comes back around to 123
+        let ids: Vec<i64> = ancestry
+            .iter()
+            .map(|a| a["snapshot-id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, [456, 123, 789]);
+        assert_eq!(ancestry[0]["in-cycle"], false);
+        assert_eq!(ancestry[1]["in-cycle"], true);
+        assert_eq!(ancestry[2]["in-cycle"], true);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_ancestry() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_branches())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "789".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Ancestry,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let ancestry: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(ancestry.len(), 2);
+        assert_eq!(ancestry[0]["snapshot-id"], 789);
+        assert_eq!(ancestry[0]["parent-snapshot-id"], 123);
+        assert_eq!(ancestry[1]["snapshot-id"], 123);
+        assert_eq!(ancestry[1]["branch-point"], true);
+        assert!(ancestry[1].get("parent-snapshot-id").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_ancestry_expired_parent() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_branches())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "790".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Ancestry,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let ancestry: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        // The walk stops at the snapshot whose parent is gone
+        assert_eq!(ancestry.len(), 1);
+        assert_eq!(ancestry[0]["snapshot-id"], 790);
+        assert_eq!(ancestry[0]["parent-snapshot-id"], 555);
+        assert_eq!(ancestry[0]["parent-expired"], true);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_manifests() -> Result<()> {
+        let metadata_json = serde_json::to_string(&day_partitioned_metadata(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let data_manifest = write_manifest_for_spec(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            day_partition_spec(),
+            vec![
+                day_partitioned_data_file(
+                    "s3://bucket/table/data/a.parquet",
+                    "2026-10-01",
+                    10,
+                    1000,
+                ),
+                day_partitioned_data_file(
+                    "s3://bucket/table/data/b.parquet",
+                    "2026-10-03",
+                    20,
+                    2000,
+                ),
+            ],
+        )
+        .await;
+        let delete_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            vec![position_delete_file(
+                "s3://bucket/table/data/d.parquet",
+                2,
+                200,
+            )],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![data_manifest, delete_manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Manifests,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let manifests: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(manifests.len(), 2);
+
+        assert_eq!(manifests[0]["path"], "s3://bucket/table/m1.avro");
+        assert_eq!(manifests[0]["content"], "data");
+        assert_eq!(manifests[0]["partition-spec-id"], 1);
+        assert_eq!(manifests[0]["added-snapshot-id"], 123);
+        assert_eq!(manifests[0]["sequence-number"], 1);
+        assert_eq!(manifests[0]["added-files-count"], 2);
+        assert_eq!(manifests[0]["added-rows-count"], 30);
+        assert_eq!(manifests[0]["existing-files-count"], 0);
+        assert!(manifests[0]["length"].as_i64().unwrap() > 0);
+        assert_eq!(
+            manifests[0]["partitions"],
+            serde_json::json!([{
+                "name": "event_day",
+                "contains-null": false,
+                "contains-nan": false,
+                "lower-bound": "2026-10-01",
+                "upper-bound": "2026-10-03"
+            }])
+        );
+
+        assert_eq!(manifests[1]["path"], "s3://bucket/table/m2.avro");
+        assert_eq!(manifests[1]["content"], "deletes");
+        assert_eq!(manifests[1]["partition-spec-id"], 0);
+        assert_eq!(manifests[1]["added-rows-count"], 2);
+        assert_eq!(manifests[1]["partitions"], serde_json::json!([]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_details() -> Result<()> {
+        use iceberg::spec::{DataFileBuilder, Datum, Literal, Struct};
+
+        let metadata_json = serde_json::to_string(&day_partitioned_metadata(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let data_file = DataFileBuilder::default()
+            .content(iceberg::spec::DataContentType::Data)
+            .file_path("s3://bucket/table/data/a.parquet".to_string())
+            .file_format(DataFileFormat::Parquet)
+            .partition(Struct::from_iter([Some(Literal::date_from_str(
+                "2026-10-01",
+            )?)]))
+            .partition_spec_id(1)
+            .record_count(10)
+            .file_size_in_bytes(1000)
+            .lower_bounds(HashMap::from([
+                (1, Datum::int(3)),
+                (2, Datum::date_from_str("2026-10-01")?),
+            ]))
+            .upper_bounds(HashMap::from([
+                (1, Datum::int(42)),
+                (2, Datum::date_from_str("2026-10-01")?),
+            ]))
+            .null_value_counts(HashMap::from([(1, 0), (2, 4)]))
+            .value_counts(HashMap::from([(1, 10), (2, 10)]))
+            .build()?;
+        let manifest = write_manifest_for_spec(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            day_partition_spec(),
+            vec![data_file],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Files(FilesArgs {
+                    details: true,
+                    ..Default::default()
+                }),
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let files: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(files.len(), 3);
+        // Only data files carry details
+        assert!(files[0].get("record-count").is_none());
+        assert!(files[1].get("record-count").is_none());
+
+        assert_eq!(
+            files[2],
+            serde_json::json!({
+                "type": "data",
+                "path": "s3://bucket/table/data/a.parquet",
+                "content": "data",
+                "file-format": "parquet",
+                "record-count": 10,
+                "file-size-in-bytes": 1000,
+                "partition-spec-id": 1,
+                "partition": {"event_day": "2026-10-01"},
+                "sequence-number": 1,
+                "file-sequence-number": 1,
+                "lower-bounds": {"event_date": "2026-10-01", "id": 3},
+                "upper-bounds": {"event_date": "2026-10-01", "id": 42},
+                "null-value-counts": {"event_date": 4, "id": 0},
+                "value-counts": {"event_date": 10, "id": 10}
+            })
+        );
+
+        Ok(())
+    }
+
+    /// Lists the current snapshot's files, restricted to `content`
+    async fn list_files(table: &Table, content: ContentFilter) -> Result<Vec<serde_json::Value>> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Files(FilesArgs {
+                    content,
+                    ..Default::default()
+                }),
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        Ok(output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?)
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_delete_types() -> Result<()> {
+        use iceberg::spec::{DataContentType, DataFileBuilder};
+
+        let metadata_json = serde_json::to_string(&metadata_with_snapshot(
+            123,
+            "s3://bucket/table/snap-123.avro",
+        ))?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        let position_delete = DataFileBuilder::default()
+            .content(DataContentType::PositionDeletes)
+            .file_path("s3://bucket/table/data/pd.parquet".to_string())
+            .file_format(DataFileFormat::Parquet)
+            .record_count(2)
+            .file_size_in_bytes(200)
+            .referenced_data_file(Some("s3://bucket/table/data/a.parquet".to_string()))
+            .build()?;
+        let equality_delete = DataFileBuilder::default()
+            .content(DataContentType::EqualityDeletes)
+            .file_path("s3://bucket/table/data/ed.parquet".to_string())
+            .file_format(DataFileFormat::Parquet)
+            .record_count(3)
+            .file_size_in_bytes(300)
+            .equality_ids(Some(vec![1]))
+            .build()?;
+        let deletion_vector = DataFileBuilder::default()
+            .content(DataContentType::PositionDeletes)
+            .file_path("s3://bucket/table/data/dv.puffin".to_string())
+            .file_format(DataFileFormat::Puffin)
+            .record_count(1)
+            .file_size_in_bytes(100)
+            .referenced_data_file(Some("s3://bucket/table/data/a.parquet".to_string()))
+            .content_offset(Some(4))
+            .content_size_in_bytes(Some(40))
+            .build()?;
+
+        let data_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![data_file("s3://bucket/table/data/a.parquet", 10, 1000)],
+        )
+        .await;
+        let delete_manifest = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            vec![position_delete, equality_delete, deletion_vector],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![data_manifest, delete_manifest],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let all = list_files(&table, ContentFilter::All).await?;
+        assert_eq!(all.len(), 7);
+
+        let deletes = list_files(&table, ContentFilter::Deletes).await?;
+        assert_eq!(deletes.len(), 5);
+        assert_eq!(deletes[0]["type"], "manifest-list");
+        assert_eq!(deletes[1]["path"], "s3://bucket/table/m2.avro");
+        assert_eq!(
+            deletes[2],
+            serde_json::json!({
+                "type": "position-delete",
+                "path": "s3://bucket/table/data/pd.parquet",
+                "referenced-data-file": "s3://bucket/table/data/a.parquet"
+            })
+        );
+        assert_eq!(
+            deletes[3],
+            serde_json::json!({
+                "type": "equality-delete",
+                "path": "s3://bucket/table/data/ed.parquet",
+                "equality-ids": [1]
+            })
+        );
+        assert_eq!(deletes[4]["type"], "deletion-vector");
+        assert_eq!(
+            deletes[4]["referenced-data-file"],
+            "s3://bucket/table/data/a.parquet"
+        );
+
+        let data = list_files(&table, ContentFilter::Data).await?;
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[1]["path"], "s3://bucket/table/m1.avro");
+        assert_eq!(data[2]["type"], "data");
+
+        Ok(())
+    }
+
+    /// Lists the current snapshot's files with `--verify` (or `--verify-size`)
+    async fn verify_files(table: &Table, args: FilesArgs) -> (Result<()>, Vec<serde_json::Value>) {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        let result = handle_table_command(
+            table,
+            TableCommands::Snapshot {
+                snapshot_id: "current".to_string(),
+                timestamp: None,
+                command: SnapshotCmd::Files(args),
+            },
+            &mut output,
+            None,
+        )
+        .await;
+
+        let records = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        (result, records)
+    }
+
+    /// Writes a table whose snapshot has manifests `m1.avro` (with `a.parquet`)
+    /// and `m2.avro` (with `b.parquet`); both data files exist.
+    async fn create_table_with_two_manifests() -> Result<(FileIO, Table)> {
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![
+            (location, &minimal_metadata()),
+            ("s3://bucket/table/data/a.parquet", "a"),
+            ("s3://bucket/table/data/b.parquet", "b"),
+        ])
+        .await;
+        let m1 = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![data_file("s3://bucket/table/data/a.parquet", 1, 1)],
+        )
+        .await;
+        let m2 = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            123,
+            vec![data_file("s3://bucket/table/data/b.parquet", 1, 1)],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![m1, m2],
+        )
+        .await;
+        let table = load_table(&file_io, location).await?;
+        Ok((file_io, table))
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_verify_missing_manifest() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        file_io.delete("s3://bucket/table/m1.avro").await?;
+
+        let (result, records) = verify_files(
+            &table,
+            FilesArgs {
+                verify: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let paths_and_existence: Vec<(&str, bool)> = records
+            .iter()
+            .map(|record| {
+                (
+                    record["path"].as_str().unwrap(),
+                    record["exists"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        // The remaining manifest is still listed
+        assert_eq!(
+            paths_and_existence,
+            vec![
+                ("s3://bucket/table/snap-123.avro", true),
+                ("s3://bucket/table/m1.avro", false),
+                ("s3://bucket/table/m2.avro", true),
+                ("s3://bucket/table/data/b.parquet", true),
+            ]
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "table is corrupt - 1 file(s) missing"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_verify_size() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        // Manifests record both data files as 1 byte
+        file_io
+            .new_output("s3://bucket/table/data/b.parquet")?
+            .write(bytes::Bytes::from_static(b"bbb"))
+            .await?;
+
+        let (result, records) = verify_files(
+            &table,
+            FilesArgs {
+                verify_size: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(records.len(), 5);
+        assert!(records[2].get("size-mismatch").is_none());
+        assert_eq!(
+            records[4],
+            serde_json::json!({
+                "type": "data",
+                "path": "s3://bucket/table/data/b.parquet",
+                "exists": true,
+                "size-mismatch": {"expected": 1, "actual": 3}
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "table is corrupt - 1 file(s) with unexpected size"
+        );
+
+        // Plain --verify only checks existence
+        let (result, records) = verify_files(
+            &table,
+            FilesArgs {
+                verify: true,
+                ..Default::default()
+            },
+        )
+        .await;
+        result?;
+        assert!(records[4].get("size-mismatch").is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_deep() -> Result<()> {
+        let (_file_io, table) = create_table_with_two_manifests().await?;
+
+        // The fixture's data files are single bytes rather than real Parquet files
+        let (result, records) = verify_files(
+            &table,
+            FilesArgs {
+                deep: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(records.len(), 5);
+        assert!(records[1].get("failures").is_none());
+        assert_eq!(
+            records[2],
+            serde_json::json!({
+                "type": "data",
+                "path": "s3://bucket/table/data/a.parquet",
+                "exists": true,
+                "failures": ["file is too small to be Parquet (1 bytes)"]
+            })
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "table is corrupt - 2 file(s) failed deep verification"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_report() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        file_io.delete("s3://bucket/table/data/a.parquet").await?;
+        file_io
+            .new_output("s3://bucket/table/data/b.parquet")?
+            .write(bytes::Bytes::from_static(b"bbb"))
+            .await?;
+        let report_dir = tempfile::tempdir()?;
+        let report_path = report_dir.path().join("report.json");
+
+        let (result, _) = verify_files(
+            &table,
+            FilesArgs {
+                verify_size: true,
+                report: Some(report_path.clone()),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(result.is_err());
+
+        let mut report: serde_json::Value = serde_json::from_slice(&std::fs::read(&report_path)?)?;
+        assert!(report["duration-ms"].is_u64());
+        report.as_object_mut().unwrap().remove("duration-ms");
+        assert_eq!(
+            report,
+            serde_json::json!({
+                "snapshot-id": 123,
+                "checker": "file-io",
+                "checked-sizes": true,
+                "deep": false,
+                "files-checked": 5,
+                "file-types": {
+                    "manifest-list": {"checked": 1, "missing": 0, "size-mismatches": 0, "deep-failures": 0},
+                    "manifest": {"checked": 2, "missing": 0, "size-mismatches": 0, "deep-failures": 0},
+                    "data": {"checked": 2, "missing": 1, "size-mismatches": 1, "deep-failures": 0}
+                },
+                "missing": ["s3://bucket/table/data/a.parquet"],
+                "size-mismatches": [
+                    {"path": "s3://bucket/table/data/b.parquet", "expected": 1, "actual": 3}
+                ],
+                "deep-failures": []
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_report_on_error() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        file_io
+            .new_output("s3://bucket/table/m2.avro")?
+            .write(bytes::Bytes::from_static(b"not avro"))
+            .await?;
+        let report_dir = tempfile::tempdir()?;
+        let report_path = report_dir.path().join("report.json");
+
+        let (result, _) = verify_files(
+            &table,
+            FilesArgs {
+                report: Some(report_path.clone()),
+                ..Default::default()
+            },
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(error.downcast_ref::<ExpectedError>().is_none());
+
+        let report: serde_json::Value = serde_json::from_slice(&std::fs::read(&report_path)?)?;
+        assert_eq!(report["files-checked"], 3);
+        assert!(
+            report["error"]
+                .as_str()
+                .unwrap()
+                .starts_with("Failed to load manifest s3://bucket/table/m2.avro")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_report_write_failure_keeps_error() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        file_io
+            .new_output("s3://bucket/table/m2.avro")?
+            .write(bytes::Bytes::from_static(b"not avro"))
+            .await?;
+        let report_dir = tempfile::tempdir()?;
+
+        // The report can't be written into a missing directory
+        let (result, _) = verify_files(
+            &table,
+            FilesArgs {
+                report: Some(report_dir.path().join("missing/report.json")),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(
+            format!("{:#}", result.unwrap_err())
+                .starts_with("Failed to load manifest s3://bucket/table/m2.avro")
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_files_verify_missing_manifest_list() -> Result<()> {
+        let (file_io, table) = create_table_with_two_manifests().await?;
+        file_io.delete("s3://bucket/table/snap-123.avro").await?;
+
+        let (result, records) = verify_files(
+            &table,
+            FilesArgs {
+                verify: true,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(
+            records,
+            vec![serde_json::json!({
+                "type": "manifest-list",
+                "path": "s3://bucket/table/snap-123.avro",
+                "exists": false
+            })]
+        );
+        assert!(
+            result
+                .unwrap_err()
+                .downcast_ref::<ExpectedError>()
+                .is_some_and(|e| matches!(e, ExpectedError::Failed(_)))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_history() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_branches())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(&table, TableCommands::History, &mut output, None).await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let history: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0],
+            serde_json::json!({
+                "timestamp-ms": 1600000000000_i64,
+                "snapshot-id": 123,
+                "is-current-ancestor": true
+            })
+        );
+        assert_eq!(history[1]["snapshot-id"], 456);
+        assert_eq!(history[1]["is-current-ancestor"], true);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_metadata_log_verify() -> Result<()> {
+        let mut metadata = metadata_with_history();
+        metadata["metadata-log"] = serde_json::json!([
+            {"metadata-file": "s3://bucket/table/metadata/v1.json", "timestamp-ms": 1600000000000_i64},
+            {"metadata-file": "s3://bucket/table/metadata/v2.json", "timestamp-ms": 1600000100000_i64}
+        ]);
+        let metadata_json = serde_json::to_string(&metadata)?;
+        let location = "s3://bucket/table/metadata/v3.json";
+        let file_io = create_memory_file_io(vec![
+            (location, &metadata_json),
+            ("s3://bucket/table/metadata/v2.json", "{}"),
+        ])
+        .await;
+        let table = load_table(&file_io, location).await?;
+
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            &table,
+            TableCommands::MetadataLog { verify: true },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        let metadata_log: Vec<serde_json::Value> = output_str
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<std::result::Result<_, _>>()?;
+
+        assert_eq!(metadata_log.len(), 2);
+        assert_eq!(
+            metadata_log[0]["metadata-file"],
+            "s3://bucket/table/metadata/v1.json"
+        );
+        assert_eq!(metadata_log[0]["exists"], false);
+        assert_eq!(metadata_log[1]["timestamp-ms"], 1600000100000_i64);
+        assert_eq!(metadata_log[1]["exists"], true);
+
+        Ok(())
+    }
+
+    async fn show_snapshot_as_of(table: &Table, timestamp: &str) -> Result<serde_json::Value> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        handle_table_command(
+            table,
+            TableCommands::Snapshot {
+                snapshot_id: "as-of".to_string(),
+                timestamp: Some(timestamp.to_string()),
+                command: SnapshotCmd::Info,
+            },
+            &mut output,
+            None,
+        )
+        .await?;
+
+        let output_str = String::from_utf8(buffer)?;
+        Ok(serde_json::from_str(output_str.trim())?)
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_as_of() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        // Snapshot 123 at 12:26:40, snapshot 456 at 12:28:20
+        let snapshot = show_snapshot_as_of(&table, "2020-09-13T12:26:40Z").await?;
+        assert_eq!(snapshot["snapshot-id"], 123);
+        let snapshot = show_snapshot_as_of(&table, "2020-09-13T12:28:00Z").await?;
+        assert_eq!(snapshot["snapshot-id"], 123);
+        let snapshot = show_snapshot_as_of(&table, "2020-09-13T14:28:20+02:00").await?;
+        assert_eq!(snapshot["snapshot-id"], 456);
+        let snapshot = show_snapshot_as_of(&table, "-1h").await?;
+        assert_eq!(snapshot["snapshot-id"], 456);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_handle_snapshot_as_of_before_history() -> Result<()> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+        let table = load_table(&file_io, location).await?;
+
+        let result = show_snapshot_as_of(&table, "2020-09-13T12:00:00Z").await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "2020-09-13T12:00:00.000Z predates the table's history, \
+             which starts at 2020-09-13T12:26:40.000Z"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_timestamp() -> Result<()> {
+        let now = DateTime::parse_from_rfc3339("2026-10-01T12:00:00Z")?.to_utc();
+        let noon = now.timestamp_millis();
+
+        assert_eq!(parse_timestamp("2026-10-01T12:00:00Z", now)?, noon);
+        assert_eq!(parse_timestamp(&noon.to_string(), now)?, noon);
+        assert_eq!(parse_timestamp("-90s", now)?, noon - 90_000);
+        assert_eq!(parse_timestamp("-2h", now)?, noon - 2 * 3_600_000);
+        assert_eq!(parse_timestamp("-1d", now)?, noon - 86_400_000);
+        assert!(parse_timestamp("-2y", now).is_err());
+        assert!(parse_timestamp("-h", now).is_err());
+        assert!(parse_timestamp("yesterday", now).is_err());
 
         Ok(())
     }
@@ -633,6 +2965,7 @@ mod tests {
             &table,
             TableCommands::Snapshot {
                 snapshot_id: "invalid".to_string(),
+                timestamp: None,
                 command: SnapshotCmd::Info,
             },
             &mut output,
@@ -664,6 +2997,7 @@ mod tests {
             &table,
             TableCommands::Snapshot {
                 snapshot_id: "999".to_string(),
+                timestamp: None,
                 command: SnapshotCmd::Info,
             },
             &mut output,
@@ -695,6 +3029,7 @@ mod tests {
             &table,
             TableCommands::Snapshot {
                 snapshot_id: "current".to_string(),
+                timestamp: None,
                 command: SnapshotCmd::Info,
             },
             &mut output,
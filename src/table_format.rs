@@ -0,0 +1,321 @@
+//! Renders serialized records as aligned text for `--format table`.
+//!
+//! Streams become columns, sized from their first rows; single objects become
+//! indented key/value blocks.
+
+use crate::terminal_output::DroppedColumns;
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::io::Write;
+
+/// Cells wider than this are shortened in the middle, keeping the end of long paths
+const MAX_COLUMN_WIDTH: usize = 60;
+
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Writes a stream of records as aligned columns, one row per record.
+///
+/// Columns are the union of the fields in the first `sample_rows` records; fields
+/// that only appear later aren't shown, with a warning. Records that aren't objects
+/// are shown in a single `value` column.
+pub struct TableWriter {
+    sample_rows: usize,
+    pending: Vec<Map<String, Value>>,
+    columns: Option<Vec<Column>>,
+    dropped: DroppedColumns,
+}
+
+struct Column {
+    name: String,
+    width: usize,
+}
+
+impl TableWriter {
+    pub fn new(sample_rows: usize) -> Self {
+        Self {
+            sample_rows,
+            pending: Vec::new(),
+            columns: None,
+            dropped: DroppedColumns::new(sample_rows),
+        }
+    }
+
+    pub fn write_row<W: Write>(&mut self, writer: &mut W, record: Value) -> Result<()> {
+        let row = match record {
+            Value::Object(row) => row,
+            value => Map::from_iter([("value".to_string(), value)]),
+        };
+        let Some(columns) = &self.columns else {
+            self.pending.push(row);
+            if self.pending.len() >= self.sample_rows {
+                self.flush(writer)?;
+            }
+            return Ok(());
+        };
+        for name in row.keys() {
+            if !columns.iter().any(|column| &column.name == name) {
+                self.dropped.add(name);
+            }
+        }
+        write_cells(writer, columns, &row)
+    }
+
+    /// Writes any rows still held back for sizing the columns.
+    pub fn finish<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        if self.columns.is_none() && !self.pending.is_empty() {
+            self.flush(writer)?;
+        }
+        Ok(())
+    }
+
+    fn flush<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        let mut columns: Vec<Column> = Vec::new();
+        for row in &self.pending {
+            for (name, value) in row {
+                let width = cell_text(value).chars().count();
+                match columns.iter_mut().find(|column| &column.name == name) {
+                    Some(column) => column.width = column.width.max(width),
+                    None => columns.push(Column {
+                        name: name.clone(),
+                        width: width.max(name.chars().count()),
+                    }),
+                }
+            }
+        }
+        for column in &mut columns {
+            column.width = column.width.min(MAX_COLUMN_WIDTH);
+        }
+
+        let header = columns
+            .iter()
+            .map(|column| (column.name.clone(), Value::String(column.name.clone())))
+            .collect();
+        write_cells(writer, &columns, &header)?;
+        for row in std::mem::take(&mut self.pending) {
+            write_cells(writer, &columns, &row)?;
+        }
+        self.columns = Some(columns);
+        Ok(())
+    }
+}
+
+fn write_cells<W: Write>(
+    writer: &mut W,
+    columns: &[Column],
+    row: &Map<String, Value>,
+) -> Result<()> {
+    let cells: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let text = row.get(&column.name).map(cell_text).unwrap_or_default();
+            // Later rows may not fit the width sized from the first; they push the
+            // row out of line rather than losing characters
+            let text = truncate(&text, MAX_COLUMN_WIDTH);
+            format!("{:<width$}", text, width = column.width)
+        })
+        .collect();
+    writeln!(writer, "{}", cells.join(COLUMN_SEPARATOR).trim_end())?;
+    Ok(())
+}
+
+/// Writes an object as `key: value` lines, indenting nested objects beneath their key.
+pub fn write_object<W: Write>(writer: &mut W, value: &Value) -> Result<()> {
+    let mut lines = Vec::new();
+    match value {
+        Value::Object(map) => block_lines(map, 0, &mut lines),
+        value => lines.push(cell_text(value)),
+    }
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+    Ok(())
+}
+
+fn block_lines(map: &Map<String, Value>, indent: usize, lines: &mut Vec<String>) {
+    // Align the values that share a line with their key
+    let key_width = map
+        .iter()
+        .filter(|(_, value)| !is_block(value))
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    for (key, value) in map {
+        match value {
+            Value::Object(nested) if !nested.is_empty() => {
+                lines.push(format!("{:indent$}{}:", "", key));
+                block_lines(nested, indent + 2, lines);
+            }
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                lines.push(format!("{:indent$}{}:", "", key));
+                for item in items {
+                    let start = lines.len();
+                    match item {
+                        Value::Object(nested) => block_lines(nested, indent + 4, lines),
+                        value => {
+                            lines.push(format!("{:w$}{}", "", cell_text(value), w = indent + 4))
+                        }
+                    }
+                    // Mark where each item starts, as in YAML
+                    if let Some(first) = lines.get_mut(start) {
+                        first.replace_range(indent + 2..indent + 4, "- ");
+                    }
+                }
+            }
+            value => {
+                let label = format!("{}:", key);
+                lines.push(
+                    format!(
+                        "{:indent$}{:<width$} {}",
+                        "",
+                        label,
+                        cell_text(value),
+                        width = key_width + 1
+                    )
+                    .trim_end()
+                    .to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Shows strings without quotes, nulls as blanks, scalar arrays as comma-separated
+/// lists, and anything else as compact JSON.
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if !items.is_empty() && !items.iter().any(is_nested) => {
+            items.iter().map(cell_text).collect::<Vec<_>>().join(", ")
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Whether a value is shown on the lines below its key, rather than beside it
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Object(nested) => !nested.is_empty(),
+        Value::Array(items) => items.iter().any(Value::is_object),
+        _ => false,
+    }
+}
+
+fn is_nested(value: &Value) -> bool {
+    value.is_object() || value.is_array()
+}
+
+/// Shortens text to `width` characters by replacing its middle with an ellipsis.
+fn truncate(text: &str, width: usize) -> String {
+    let length = text.chars().count();
+    if length <= width {
+        return text.to_string();
+    }
+    let tail = width / 2;
+    let head = width.saturating_sub(tail + 1);
+    let start: String = text.chars().take(head).collect();
+    let end: String = text.chars().skip(length - tail).collect();
+    format!("{}…{}", start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::terminal_output::DEFAULT_SAMPLE_ROWS;
+    use serde_json::json;
+
+    fn render_rows(records: Vec<Value>) -> String {
+        let mut buffer = Vec::new();
+        let mut table = TableWriter::new(DEFAULT_SAMPLE_ROWS);
+        for record in records {
+            table.write_row(&mut buffer, record).unwrap();
+        }
+        table.finish(&mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    #[test]
+    fn test_rows_are_aligned() {
+        let output = render_rows(vec![
+            json!({"snapshot-id": 1, "operation": "append"}),
+            json!({"snapshot-id": 1234567, "operation": "overwrite", "parent": 1}),
+        ]);
+
+        assert_eq!(
+            output,
+            "snapshot-id  operation  parent\n\
+             1            append\n\
+             1234567      overwrite  1\n"
+        );
+    }
+
+    #[test]
+    fn test_scalar_rows() {
+        let output = render_rows(vec![json!("db.a"), json!("db.b")]);
+        assert_eq!(output, "value\ndb.a\ndb.b\n");
+    }
+
+    #[test]
+    fn test_columns_fixed_after_sample_rows() {
+        let mut buffer = Vec::new();
+        let mut table = TableWriter::new(2);
+        for record in [
+            json!({"id": 1}),
+            json!({"id": 2}),
+            json!({"id": 123456, "late": true}),
+        ] {
+            table.write_row(&mut buffer, record).unwrap();
+        }
+        table.finish(&mut buffer).unwrap();
+
+        // Wider than the column, and its new field is left out with a warning
+        assert_eq!(String::from_utf8(buffer).unwrap(), "id\n1\n2\n123456\n");
+        assert_eq!(table.dropped.names, ["late"]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(
+            truncate("s3://bucket/table/data/file.parquet", 16),
+            "s3://bu….parquet"
+        );
+        assert_eq!(
+            truncate("s3://bucket/table/data/file.parquet", 16)
+                .chars()
+                .count(),
+            16
+        );
+    }
+
+    #[test]
+    fn test_write_object() {
+        let mut buffer = Vec::new();
+        write_object(
+            &mut buffer,
+            &json!({
+                "name": "db",
+                "location": null,
+                "properties": {"owner": "me", "comment": "test"},
+                "fields": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}],
+                "ids": [1, 2]
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "name:     db\n\
+             location:\n\
+             properties:\n\
+             \x20 owner:   me\n\
+             \x20 comment: test\n\
+             fields:\n\
+             \x20 - id:   1\n\
+             \x20   name: a\n\
+             \x20 - id:   2\n\
+             \x20   name: b\n\
+             ids:      1, 2\n"
+        );
+    }
+}
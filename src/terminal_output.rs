@@ -1,17 +1,32 @@
+use crate::cli::OutputFormat;
+use crate::columnar_format::{ColumnarFormat, ColumnarWriter};
+use crate::delimited_format::DelimitedWriter;
+use crate::record_filter::RecordFilter;
+use crate::table_format::{TableWriter, write_object};
 use anyhow::Result;
 use futures::{Stream, StreamExt};
 use serde::Serialize;
+use serde_json::Value;
 use std::io::{Stdout, Write};
 
-/// Terminal output handler for displaying JSON objects and streams
+/// Records read before table, CSV and TSV columns are fixed, unless `--sample-rows` says otherwise
+pub const DEFAULT_SAMPLE_ROWS: usize = 100;
+
+/// Terminal output handler for displaying objects and streams in the chosen format
 pub struct TerminalOutput<W: Write> {
     writer: W,
+    format: OutputFormat,
+    filter: RecordFilter,
+    sample_rows: usize,
 }
 
 impl Default for TerminalOutput<Stdout> {
     fn default() -> Self {
         Self {
             writer: std::io::stdout(),
+            format: OutputFormat::default(),
+            filter: RecordFilter::default(),
+            sample_rows: DEFAULT_SAMPLE_ROWS,
         }
     }
 }
@@ -26,29 +41,192 @@ impl TerminalOutput<Stdout> {
 impl<W: Write> TerminalOutput<W> {
     /// Create a TerminalOutput with a custom writer
     pub fn with_writer(writer: W) -> Self {
-        Self { writer }
+        Self {
+            writer,
+            format: OutputFormat::default(),
+            filter: RecordFilter::default(),
+            sample_rows: DEFAULT_SAMPLE_ROWS,
+        }
+    }
+
+    /// Use a different output format
+    pub fn with_format(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Select fields from, and filter, every record before it's displayed
+    pub fn with_filter(mut self, filter: RecordFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Read this many records before fixing the columns of table, CSV and TSV output
+    pub fn with_sample_rows(mut self, sample_rows: usize) -> Self {
+        self.sample_rows = sample_rows;
+        self
     }
 
-    /// Display a single object as pretty-printed JSON
+    /// Display a single object, as pretty-printed JSON by default
     pub fn display_object<T: Serialize>(&mut self, item: &T) -> Result<()> {
-        let json = serde_json::to_string_pretty(item)?;
-        writeln!(self.writer, "{}", json)?;
+        let Some(record) = self.record(item)? else {
+            return Ok(());
+        };
+        match self.format {
+            OutputFormat::Json => {
+                let json = serde_json::to_string_pretty(&record)?;
+                writeln!(self.writer, "{}", json)?;
+            }
+            OutputFormat::Table => write_object(&mut self.writer, &record)?,
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let mut rows = DelimitedWriter::new(self.delimiter(), self.sample_rows);
+                rows.write_row(&mut self.writer, &record)?;
+                rows.finish(&mut self.writer)?;
+            }
+            OutputFormat::Yaml => write!(self.writer, "{}", serde_norway::to_string(&record)?)?,
+            OutputFormat::Parquet | OutputFormat::Arrow => {
+                let mut rows = ColumnarWriter::new(self.columnar_format());
+                rows.add_row(record)?;
+                rows.finish(&mut self.writer)?;
+            }
+        }
         Ok(())
     }
 
-    /// Display items from a stream as JSON Lines (JSONL) format
+    /// Display items from a stream, as JSON Lines (JSONL) by default
     pub async fn display_stream<T: Serialize>(
         &mut self,
         stream: impl Stream<Item = Result<T>>,
     ) -> Result<()> {
         tokio::pin!(stream);
-        while let Some(result) = stream.next().await {
-            let item = result?;
-            let json = serde_json::to_string(&item)?;
-            writeln!(self.writer, "{}", json)?;
+        match self.format {
+            OutputFormat::Json => {
+                while let Some(result) = stream.next().await {
+                    let Some(record) = self.record(&result?)? else {
+                        continue;
+                    };
+                    let json = serde_json::to_string(&record)?;
+                    writeln!(self.writer, "{}", json)?;
+                }
+            }
+            OutputFormat::Table => {
+                let mut table = TableWriter::new(self.sample_rows);
+                while let Some(result) = stream.next().await {
+                    // Show the rows read so far before reporting an error
+                    let item = match result {
+                        Ok(item) => item,
+                        Err(e) => {
+                            table.finish(&mut self.writer)?;
+                            return Err(e);
+                        }
+                    };
+                    if let Some(record) = self.record(&item)? {
+                        table.write_row(&mut self.writer, record)?;
+                    }
+                }
+                table.finish(&mut self.writer)?;
+            }
+            OutputFormat::Csv | OutputFormat::Tsv => {
+                let mut rows = DelimitedWriter::new(self.delimiter(), self.sample_rows);
+                while let Some(result) = stream.next().await {
+                    // Write the rows read so far before reporting an error
+                    let item = match result {
+                        Ok(item) => item,
+                        Err(e) => {
+                            rows.finish(&mut self.writer)?;
+                            return Err(e);
+                        }
+                    };
+                    if let Some(record) = self.record(&item)? {
+                        rows.write_row(&mut self.writer, &record)?;
+                    }
+                }
+                rows.finish(&mut self.writer)?;
+            }
+            OutputFormat::Yaml => {
+                while let Some(result) = stream.next().await {
+                    let Some(record) = self.record(&result?)? else {
+                        continue;
+                    };
+                    let yaml = serde_norway::to_string(&record)?;
+                    write!(self.writer, "---\n{}", yaml)?;
+                }
+            }
+            OutputFormat::Parquet | OutputFormat::Arrow => {
+                let mut rows = ColumnarWriter::new(self.columnar_format());
+                while let Some(result) = stream.next().await {
+                    // Write the rows read so far before reporting an error
+                    let item = match result {
+                        Ok(item) => item,
+                        Err(e) => {
+                            rows.finish(&mut self.writer)?;
+                            return Err(e);
+                        }
+                    };
+                    if let Some(record) = self.record(&item)? {
+                        rows.add_row(record)?;
+                    }
+                }
+                rows.finish(&mut self.writer)?;
+            }
         }
         Ok(())
     }
+
+    /// Flush any buffered output, e.g. before closing an `--output` file
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Serializes an item, returning `None` if the filter drops it
+    fn record<T: Serialize>(&self, item: &T) -> Result<Option<Value>> {
+        Ok(self.filter.apply(serde_json::to_value(item)?))
+    }
+
+    fn delimiter(&self) -> u8 {
+        match self.format {
+            OutputFormat::Tsv => b'\t',
+            _ => b',',
+        }
+    }
+
+    fn columnar_format(&self) -> ColumnarFormat {
+        match self.format {
+            OutputFormat::Arrow => ColumnarFormat::Arrow,
+            _ => ColumnarFormat::Parquet,
+        }
+    }
+}
+
+/// Fields first seen after a writer's columns were fixed, which it leaves out.
+///
+/// Each is reported on stderr the first time it's seen, so that a long stream
+/// doesn't lose data silently.
+pub(crate) struct DroppedColumns {
+    sample_rows: usize,
+    pub(crate) names: Vec<String>,
+}
+
+impl DroppedColumns {
+    pub(crate) fn new(sample_rows: usize) -> Self {
+        Self {
+            sample_rows,
+            names: Vec::new(),
+        }
+    }
+
+    /// Notes that `name` isn't one of the columns, warning if it's new.
+    pub(crate) fn add(&mut self, name: &str) {
+        if !self.names.iter().any(|dropped| dropped == name) {
+            eprintln!(
+                "WARNING: Leaving out '{}', which isn't in the first {} records \
+                 (use --sample-rows to read more before choosing columns)",
+                name, self.sample_rows
+            );
+            self.names.push(name.to_string());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +342,131 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("test error"));
     }
 
+    #[tokio::test]
+    async fn test_display_stream_table() -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer).with_format(OutputFormat::Table);
+
+        let items = vec![
+            TestData {
+                name: "first".to_string(),
+                value: 1,
+            },
+            TestData {
+                name: "second".to_string(),
+                value: 22,
+            },
+        ];
+        output
+            .display_stream(stream::iter(items.into_iter().map(Ok)))
+            .await?;
+        output.display_object(&TestData {
+            name: "third".to_string(),
+            value: 3,
+        })?;
+
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            "name    value\nfirst   1\nsecond  22\nname:  third\nvalue: 3\n"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_display_stream_csv_with_error() {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer).with_format(OutputFormat::Csv);
+
+        let items: Vec<Result<TestData>> = vec![
+            Ok(TestData {
+                name: "first".to_string(),
+                value: 1,
+            }),
+            Err(anyhow::anyhow!("test error")),
+        ];
+        let result = output.display_stream(stream::iter(items)).await;
+
+        assert!(result.is_err());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "name,value\nfirst,1\n");
+    }
+
+    #[tokio::test]
+    async fn test_display_yaml() -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer).with_format(OutputFormat::Yaml);
+
+        output.display_object(&TestData {
+            name: "only".to_string(),
+            value: 0,
+        })?;
+        let items = vec![
+            TestData {
+                name: "first".to_string(),
+                value: 1,
+            },
+            TestData {
+                name: "10".to_string(),
+                value: 2,
+            },
+        ];
+        output
+            .display_stream(stream::iter(items.into_iter().map(Ok)))
+            .await?;
+
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            "name: only\nvalue: 0\n---\nname: first\nvalue: 1\n---\nname: '10'\nvalue: 2\n"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_display_with_filter() -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer).with_filter(RecordFilter::new(
+            vec!["name".to_string()],
+            vec!["value >= 2".parse().unwrap()],
+        ));
+
+        let items = (1..=3).map(|value| {
+            Ok(TestData {
+                name: format!("item-{}", value),
+                value,
+            })
+        });
+        output.display_stream(stream::iter(items)).await?;
+        output.display_object(&TestData {
+            name: "dropped".to_string(),
+            value: 0,
+        })?;
+
+        assert_eq!(
+            String::from_utf8(buffer)?,
+            "{\"name\":\"item-2\"}\n{\"name\":\"item-3\"}\n"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_display_stream_arrow() -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer).with_format(OutputFormat::Arrow);
+
+        let items = vec![TestData {
+            name: "first".to_string(),
+            value: 1,
+        }];
+        output
+            .display_stream(stream::iter(items.into_iter().map(Ok)))
+            .await?;
+
+        assert!(buffer.starts_with(b"ARROW1"));
+        Ok(())
+    }
+
     #[test]
     fn test_new_creates_stdout_output() {
         let _output = TerminalOutput::new();
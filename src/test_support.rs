@@ -0,0 +1,301 @@
+//! Shared fixtures for unit tests: in-memory FileIO, table metadata, and
+//! manifest/manifest list writers.
+
+use iceberg::io::FileIO;
+use iceberg::spec::{
+    DataContentType, DataFile, DataFileBuilder, DataFileFormat, Literal, ManifestFile,
+    ManifestListWriter, ManifestWriterBuilder, NestedField, PartitionSpec, PrimitiveType, Schema,
+    SchemaRef, Struct, Transform, Type,
+};
+use std::sync::Arc;
+
+pub(crate) async fn create_memory_file_io(files: Vec<(&str, &str)>) -> FileIO {
+    let file_io = FileIO::new_with_memory();
+
+    for (path, content) in files {
+        let output_file = file_io.new_output(path).unwrap();
+        output_file
+            .write(bytes::Bytes::from(content.to_string()))
+            .await
+            .unwrap();
+    }
+
+    file_io
+}
+
+/// Returns metadata for an empty Iceberg table (no snapshots)
+pub(crate) fn empty_metadata() -> serde_json::Value {
+    serde_json::json!({
+        "format-version": 2,
+        "table-uuid": "9c2c0c2c-9c2c-9c2c-9c2c-9c2c0c2c0c2c",
+        "location": "s3://bucket/table",
+        "last-sequence-number": 1,
+        "last-updated-ms": 1600000000000_i64,
+        "last-column-id": 2,
+        "current-schema-id": 0,
+        "schemas": [
+            {
+                "type": "struct",
+                "schema-id": 0,
+                "fields": [
+                    {
+                        "id": 1,
+                        "name": "id",
+                        "required": true,
+                        "type": "int"
+                    },
+                    {
+                        "id": 2,
+                        "name": "event_date",
+                        "required": false,
+                        "type": "date"
+                    }
+                ]
+            }
+        ],
+        "default-spec-id": 0,
+        "partition-specs": [{"spec-id": 0, "fields": []}],
+        "last-partition-id": 999,
+        "default-sort-order-id": 0,
+        "sort-orders": [{"order-id": 0, "fields": []}],
+        "properties": {},
+        "refs": {},
+        "snapshots": [],
+        "snapshot-log": [],
+        "metadata-log": []
+    })
+}
+
+/// Returns metadata with a single snapshot added
+pub(crate) fn metadata_with_snapshot(snapshot_id: i64, manifest_list: &str) -> serde_json::Value {
+    let mut metadata = empty_metadata();
+
+    metadata["current-snapshot-id"] = serde_json::json!(snapshot_id);
+    metadata["snapshots"] = serde_json::json!([
+        {
+            "snapshot-id": snapshot_id,
+            "sequence-number": 1,
+            "timestamp-ms": 1600000000000_i64,
+            "manifest-list": manifest_list,
+            "summary": { "operation": "append" },
+            "schema-id": 0
+        }
+    ]);
+
+    metadata
+}
+
+/// Returns metadata with two snapshots, a snapshot log, and several refs
+pub(crate) fn metadata_with_history() -> serde_json::Value {
+    let mut metadata = metadata_with_snapshot(123, "s3://bucket/table/snap-123.avro");
+
+    metadata["last-sequence-number"] = serde_json::json!(2);
+    metadata["last-updated-ms"] = serde_json::json!(1600000200000_i64);
+    metadata["current-snapshot-id"] = serde_json::json!(456);
+    metadata["snapshots"] = serde_json::json!([
+        {
+            "snapshot-id": 123,
+            "sequence-number": 1,
+            "timestamp-ms": 1600000000000_i64,
+            "manifest-list": "s3://bucket/table/snap-123.avro",
+            "summary": { "operation": "append" },
+            "schema-id": 0
+        },
+        {
+            "snapshot-id": 456,
+            "parent-snapshot-id": 123,
+            "sequence-number": 2,
+            "timestamp-ms": 1600000100000_i64,
+            "manifest-list": "s3://bucket/table/snap-456.avro",
+            "summary": { "operation": "overwrite" },
+            "schema-id": 0
+        }
+    ]);
+    metadata["snapshot-log"] = serde_json::json!([
+        {"snapshot-id": 123, "timestamp-ms": 1600000000000_i64},
+        {"snapshot-id": 456, "timestamp-ms": 1600000100000_i64}
+    ]);
+    metadata["refs"] = serde_json::json!({
+        "main": {"snapshot-id": 456, "type": "branch"},
+        "audit": {"snapshot-id": 123, "type": "branch", "min-snapshots-to-keep": 5},
+        "v1": {"snapshot-id": 123, "type": "tag", "max-ref-age-ms": 86400000}
+    });
+
+    metadata
+}
+
+/// Returns the schema used by the metadata fixtures
+pub(crate) fn test_schema() -> SchemaRef {
+    Arc::new(
+        Schema::builder()
+            .with_schema_id(0)
+            .with_fields(vec![
+                NestedField::required(1, "id", Type::Primitive(PrimitiveType::Int)).into(),
+                NestedField::optional(2, "event_date", Type::Primitive(PrimitiveType::Date)).into(),
+            ])
+            .build()
+            .unwrap(),
+    )
+}
+
+/// Returns partition spec 1, which partitions by day of `event_date`
+pub(crate) fn day_partition_spec() -> PartitionSpec {
+    PartitionSpec::builder(test_schema())
+        .with_spec_id(1)
+        .add_partition_field("event_date", "event_day", Transform::Day)
+        .unwrap()
+        .build()
+        .unwrap()
+}
+
+/// Returns metadata with a snapshot, where spec 1 (by day of `event_date`) is the default
+pub(crate) fn day_partitioned_metadata(snapshot_id: i64, manifest_list: &str) -> serde_json::Value {
+    let mut metadata = metadata_with_snapshot(snapshot_id, manifest_list);
+
+    metadata["default-spec-id"] = serde_json::json!(1);
+    metadata["last-partition-id"] = serde_json::json!(1000);
+    metadata["partition-specs"] = serde_json::json!([
+        {"spec-id": 0, "fields": []},
+        serde_json::to_value(day_partition_spec()).unwrap()
+    ]);
+
+    metadata
+}
+
+/// Returns an unpartitioned Parquet data file
+pub(crate) fn data_file(path: &str, record_count: u64, file_size_in_bytes: u64) -> DataFile {
+    DataFileBuilder::default()
+        .content(DataContentType::Data)
+        .file_path(path.to_string())
+        .file_format(DataFileFormat::Parquet)
+        .record_count(record_count)
+        .file_size_in_bytes(file_size_in_bytes)
+        .build()
+        .unwrap()
+}
+
+/// Returns a Parquet data file in the `day_partition_spec` partition for `date`
+pub(crate) fn day_partitioned_data_file(
+    path: &str,
+    date: &str,
+    record_count: u64,
+    file_size_in_bytes: u64,
+) -> DataFile {
+    DataFileBuilder::default()
+        .content(DataContentType::Data)
+        .file_path(path.to_string())
+        .file_format(DataFileFormat::Parquet)
+        .partition(Struct::from_iter([Some(
+            Literal::date_from_str(date).unwrap(),
+        )]))
+        .partition_spec_id(1)
+        .record_count(record_count)
+        .file_size_in_bytes(file_size_in_bytes)
+        .build()
+        .unwrap()
+}
+
+/// Returns an unpartitioned Parquet position delete file
+pub(crate) fn position_delete_file(
+    path: &str,
+    record_count: u64,
+    file_size_in_bytes: u64,
+) -> DataFile {
+    DataFileBuilder::default()
+        .content(DataContentType::PositionDeletes)
+        .file_path(path.to_string())
+        .file_format(DataFileFormat::Parquet)
+        .record_count(record_count)
+        .file_size_in_bytes(file_size_in_bytes)
+        .build()
+        .unwrap()
+}
+
+/// Returns a deletion vector for `data_file`, stored at `content_offset` in a Puffin file
+pub(crate) fn deletion_vector(
+    path: &str,
+    data_file: &str,
+    content_offset: i64,
+    content_size_in_bytes: i64,
+) -> DataFile {
+    DataFileBuilder::default()
+        .content(DataContentType::PositionDeletes)
+        .file_path(path.to_string())
+        .file_format(DataFileFormat::Puffin)
+        .record_count(1)
+        .file_size_in_bytes(1000)
+        .referenced_data_file(Some(data_file.to_string()))
+        .content_offset(Some(content_offset))
+        .content_size_in_bytes(Some(content_size_in_bytes))
+        .build()
+        .unwrap()
+}
+
+/// Writes a manifest containing `files` as added entries.
+///
+/// Writes a delete manifest if the first file is a delete file.
+pub(crate) async fn write_manifest(
+    file_io: &FileIO,
+    path: &str,
+    snapshot_id: i64,
+    files: Vec<DataFile>,
+) -> ManifestFile {
+    write_manifest_for_spec(
+        file_io,
+        path,
+        snapshot_id,
+        PartitionSpec::unpartition_spec(),
+        files,
+    )
+    .await
+}
+
+/// Writes a manifest for a given partition spec containing `files` as added entries.
+pub(crate) async fn write_manifest_for_spec(
+    file_io: &FileIO,
+    path: &str,
+    snapshot_id: i64,
+    partition_spec: PartitionSpec,
+    files: Vec<DataFile>,
+) -> ManifestFile {
+    let builder = ManifestWriterBuilder::new(
+        file_io.new_output(path).unwrap(),
+        Some(snapshot_id),
+        None,
+        test_schema(),
+        partition_spec,
+    );
+    let is_deletes = files
+        .first()
+        .is_some_and(|file| file.content_type() != DataContentType::Data);
+    let mut writer = if is_deletes {
+        builder.build_v2_deletes()
+    } else {
+        builder.build_v2_data()
+    };
+
+    for file in files {
+        writer.add_file(file, -1).unwrap();
+    }
+
+    writer.write_manifest_file().await.unwrap()
+}
+
+/// Writes a manifest list referencing `manifests`
+pub(crate) async fn write_manifest_list(
+    file_io: &FileIO,
+    path: &str,
+    snapshot_id: i64,
+    parent_snapshot_id: Option<i64>,
+    sequence_number: i64,
+    manifests: Vec<ManifestFile>,
+) {
+    let mut writer = ManifestListWriter::v2(
+        file_io.new_output(path).unwrap(),
+        snapshot_id,
+        parent_snapshot_id,
+        sequence_number,
+    );
+    writer.add_manifests(manifests.into_iter()).unwrap();
+    writer.close().await.unwrap();
+}
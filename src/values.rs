@@ -0,0 +1,116 @@
+//! Human-readable rendering of Iceberg values.
+//!
+//! Bounds and partition values are stored as raw bytes or untyped literals.
+//! These helpers render them with Iceberg's JSON single-value serialization,
+//! so that dates, timestamps and decimals come out as readable strings.
+
+use iceberg::spec::{Datum, Literal, PartitionSpec, Struct, StructType, TableMetadata, Type};
+use serde_json::{Map, Value};
+
+/// Renders a typed value as JSON.
+pub fn datum_to_json(datum: &Datum) -> Value {
+    literal_to_json(
+        &Literal::from(datum.clone()),
+        &Type::Primitive(datum.data_type().clone()),
+    )
+}
+
+/// Renders a literal of the given type as JSON, or `null` if they don't match.
+pub fn literal_to_json(literal: &Literal, r#type: &Type) -> Value {
+    literal.clone().try_into_json(r#type).unwrap_or(Value::Null)
+}
+
+/// Decodes a binary-serialized bound (as stored in manifests) and renders it as JSON.
+///
+/// Falls back to a hex string if the type is unknown or the bytes don't decode.
+pub fn bound_to_json(bytes: &[u8], r#type: Option<&Type>) -> Value {
+    r#type
+        .and_then(Type::as_primitive_type)
+        .and_then(|primitive| Datum::try_from_bytes(bytes, primitive.clone()).ok())
+        .map(|datum| datum_to_json(&datum))
+        .unwrap_or_else(|| Value::String(hex(bytes)))
+}
+
+/// Returns the partition tuple type of a spec.
+///
+/// Binds against the current schema first, falling back to older schemas so
+/// that specs whose source columns have since been dropped still resolve.
+pub fn partition_type(metadata: &TableMetadata, spec: &PartitionSpec) -> Option<StructType> {
+    std::iter::once(metadata.current_schema())
+        .chain(metadata.schemas_iter())
+        .find_map(|schema| spec.partition_type(schema).ok())
+}
+
+/// Renders a partition tuple as a JSON object keyed by partition field name.
+///
+/// Values render as `null` if the spec can't be found or bound to a schema.
+pub fn partition_to_json(
+    metadata: &TableMetadata,
+    partition_spec_id: i32,
+    partition: &Struct,
+) -> Map<String, Value> {
+    let spec = metadata.partition_spec_by_id(partition_spec_id);
+    let fields = spec.map(|spec| spec.fields()).unwrap_or_default();
+    let partition_type = spec.and_then(|spec| partition_type(metadata, spec));
+
+    partition
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let name = fields
+                .get(index)
+                .map(|field| field.name.clone())
+                .unwrap_or_else(|| index.to_string());
+            let field_type = partition_type
+                .as_ref()
+                .and_then(|partition_type| partition_type.fields().get(index));
+            let value = match (value, field_type) {
+                (Some(literal), Some(field)) => literal_to_json(literal, &field.field_type),
+                _ => Value::Null,
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iceberg::spec::PrimitiveType;
+
+    #[test]
+    fn test_datum_to_json() {
+        assert_eq!(datum_to_json(&Datum::int(42)), serde_json::json!(42));
+        assert_eq!(
+            datum_to_json(&Datum::date_from_str("2026-10-01").unwrap()),
+            serde_json::json!("2026-10-01")
+        );
+        assert_eq!(
+            datum_to_json(&Datum::string("hello")),
+            serde_json::json!("hello")
+        );
+    }
+
+    #[test]
+    fn test_bound_to_json() {
+        let date_type = Type::Primitive(PrimitiveType::Date);
+        let bytes = Datum::date_from_str("2026-10-01")
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+
+        assert_eq!(
+            bound_to_json(&bytes, Some(&date_type)),
+            serde_json::json!("2026-10-01")
+        );
+        // Without a type, fall back to hex
+        assert_eq!(
+            bound_to_json(&[0x01, 0xab], None),
+            serde_json::json!("01ab")
+        );
+    }
+}
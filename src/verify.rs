@@ -0,0 +1,413 @@
+//! Verifies that the files of many snapshots exist, checking each file once.
+//!
+//! Snapshots share most of their manifests and data files, so rather than
+//! walking each snapshot separately, this collects the union of their files,
+//! remembers which snapshots reach each one, and checks each file once.
+
+use crate::error::ExpectedError;
+use crate::file_existence::{FileExistenceChecker, create_existence_checker};
+use crate::table_commands::{FileType, data_file_prefix, load_manifest_list, snapshot_refs};
+use crate::terminal_output::TerminalOutput;
+use anyhow::{Context, Result};
+use futures::{StreamExt, stream};
+use iceberg::spec::{ManifestFile, ManifestStatus, SnapshotRef};
+use iceberg::table::Table;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+/// One line of `verify` output: a missing file, or the final summary.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+enum VerifyRecord {
+    Missing(MissingFile),
+    Summary(VerifySummary),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct MissingFile {
+    r#type: FileType,
+    path: String,
+    /// The snapshots that can't be read because this file is missing
+    snapshot_ids: BTreeSet<i64>,
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    refs: BTreeSet<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct VerifySummary {
+    snapshots_checked: usize,
+    manifest_lists_checked: usize,
+    manifests_checked: usize,
+    files_checked: usize,
+    missing_files: usize,
+    broken_snapshot_ids: BTreeSet<i64>,
+    broken_refs: BTreeSet<String>,
+}
+
+/// A file to check, with the snapshots that reach it.
+struct Reachable<T> {
+    item: T,
+    snapshot_ids: BTreeSet<i64>,
+}
+
+/// Which snapshots `verify` checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyScope {
+    Current,
+    Refs,
+    All,
+}
+
+/// How many data and delete files are checked for at once
+const EXISTENCE_CONCURRENCY: usize = 16;
+
+/// Verifies the files of the snapshots in `scope`, streaming each missing file
+/// followed by a summary. Fails if any file is missing.
+pub async fn handle_verify<W: Write>(
+    table: &Table,
+    scope: VerifyScope,
+    s3_client: Option<&aws_sdk_s3::Client>,
+    output: &mut TerminalOutput<W>,
+) -> Result<()> {
+    let metadata = table.metadata();
+    let file_io = table.file_io();
+
+    let mut refs_by_snapshot: BTreeMap<i64, BTreeSet<String>> = BTreeMap::new();
+    for (name, reference) in snapshot_refs(metadata)? {
+        refs_by_snapshot
+            .entry(reference.snapshot_id)
+            .or_default()
+            .insert(name);
+    }
+
+    let snapshots: Vec<&SnapshotRef> = match scope {
+        VerifyScope::Current => metadata.current_snapshot().into_iter().collect(),
+        VerifyScope::Refs => refs_by_snapshot
+            .keys()
+            .filter_map(|snapshot_id| metadata.snapshot_by_id(*snapshot_id))
+            .collect(),
+        VerifyScope::All => metadata.snapshots().collect(),
+    };
+
+    let mut missing: Vec<MissingFile> = Vec::new();
+    let mut missing_file = |r#type, path: String, snapshot_ids: BTreeSet<i64>| {
+        let refs = snapshot_ids
+            .iter()
+            .filter_map(|snapshot_id| refs_by_snapshot.get(snapshot_id))
+            .flatten()
+            .cloned()
+            .collect();
+        missing.push(MissingFile {
+            r#type,
+            path,
+            snapshot_ids,
+            refs,
+        });
+    };
+
+    // Manifest lists are per snapshot; gather the manifests they share
+    let mut manifests: BTreeMap<String, Reachable<ManifestFile>> = BTreeMap::new();
+    for snapshot in &snapshots {
+        let snapshot_id = snapshot.snapshot_id();
+        let manifest_list = match load_manifest_list(table, snapshot).await {
+            Ok(manifest_list) => manifest_list,
+            Err(_) if !file_io.exists(snapshot.manifest_list()).await? => {
+                missing_file(
+                    FileType::ManifestList,
+                    snapshot.manifest_list().to_string(),
+                    BTreeSet::from([snapshot_id]),
+                );
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        for manifest_file in manifest_list.consume_entries() {
+            manifests
+                .entry(manifest_file.manifest_path.clone())
+                .or_insert_with(|| Reachable {
+                    item: manifest_file,
+                    snapshot_ids: BTreeSet::new(),
+                })
+                .snapshot_ids
+                .insert(snapshot_id);
+        }
+    }
+    let manifests_checked = manifests.len();
+
+    // Then gather the data and delete files those manifests share
+    let mut files: BTreeMap<String, Reachable<FileType>> = BTreeMap::new();
+    let mut loaded = stream::iter(manifests.into_values().map(|manifest| async move {
+        let result = manifest.item.load_manifest(file_io).await;
+        (manifest, result)
+    }))
+    .buffered(7);
+    while let Some((manifest, result)) = loaded.next().await {
+        let path = &manifest.item.manifest_path;
+        let loaded_manifest = match result {
+            Ok(loaded_manifest) => loaded_manifest,
+            Err(_) if !file_io.exists(path).await? => {
+                missing_file(FileType::Manifest, path.clone(), manifest.snapshot_ids);
+                continue;
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to load manifest {}", path)),
+        };
+        for entry in loaded_manifest.entries() {
+            if entry.status() == ManifestStatus::Deleted {
+                continue;
+            }
+            files
+                .entry(entry.file_path().to_string())
+                .or_insert_with(|| Reachable {
+                    item: FileType::of(entry.data_file()),
+                    snapshot_ids: BTreeSet::new(),
+                })
+                .snapshot_ids
+                .extend(&manifest.snapshot_ids);
+        }
+    }
+    let files_checked = files.len();
+
+    // Nothing to check means nothing to list, e.g. when every manifest is missing
+    if !files.is_empty() {
+        let existence_checker: Box<dyn FileExistenceChecker> = create_existence_checker(
+            file_io.clone(),
+            &data_file_prefix(metadata)?,
+            s3_client,
+            files_checked as u64,
+        )
+        .await?;
+        let existence_checker = existence_checker.as_ref();
+        let mut checked = stream::iter(files.into_iter().map(|(path, file)| async move {
+            let exists = existence_checker.exists(&path).await;
+            (path, file, exists)
+        }))
+        .buffered(EXISTENCE_CONCURRENCY);
+        while let Some((path, file, exists)) = checked.next().await {
+            if !exists? {
+                missing_file(file.item, path, file.snapshot_ids);
+            }
+        }
+    }
+
+    let broken_snapshot_ids: BTreeSet<i64> = missing
+        .iter()
+        .flat_map(|file| file.snapshot_ids.iter().copied())
+        .collect();
+    let broken_refs: BTreeSet<String> = missing
+        .iter()
+        .flat_map(|file| file.refs.iter().cloned())
+        .collect();
+    let summary = VerifySummary {
+        snapshots_checked: snapshots.len(),
+        manifest_lists_checked: snapshots.len(),
+        manifests_checked,
+        files_checked,
+        missing_files: missing.len(),
+        broken_snapshot_ids,
+        broken_refs,
+    };
+    let failure = (!missing.is_empty()).then(|| {
+        format!(
+            "{} snapshot(s) broken by {} missing file(s)",
+            summary.broken_snapshot_ids.len(),
+            summary.missing_files
+        )
+    });
+
+    let records = missing
+        .into_iter()
+        .map(VerifyRecord::Missing)
+        .chain(std::iter::once(VerifyRecord::Summary(summary)));
+    output.display_stream(stream::iter(records.map(Ok))).await?;
+
+    match failure {
+        Some(message) => Err(anyhow::Error::new(ExpectedError::Failed(message))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::TableCommands;
+    use crate::table_commands::{handle_table_command, load_table};
+    use crate::test_support::*;
+    use iceberg::io::FileIO;
+
+    /// Writes a table where snapshot 123 has `a.parquet`, and snapshot 456 (main)
+    /// keeps 123's manifest and adds `b.parquet`. Data files are only written if
+    /// listed in `existing`.
+    async fn create_table(existing: &[&str]) -> Result<(FileIO, Table)> {
+        let metadata_json = serde_json::to_string(&metadata_with_history())?;
+        let location = "s3://bucket/table/metadata.json";
+        let file_io = create_memory_file_io(vec![(location, &metadata_json)]).await;
+
+        for name in existing {
+            file_io
+                .new_output(format!("s3://bucket/table/data/{name}"))?
+                .write(bytes::Bytes::from_static(b"data"))
+                .await?;
+        }
+
+        let m1 = write_manifest(
+            &file_io,
+            "s3://bucket/table/m1.avro",
+            123,
+            vec![data_file("s3://bucket/table/data/a.parquet", 10, 1000)],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-123.avro",
+            123,
+            None,
+            1,
+            vec![m1.clone()],
+        )
+        .await;
+        // Carried over into 456, with the sequence number it was assigned in 123
+        let mut m1_carried = m1.clone();
+        m1_carried.sequence_number = 1;
+        m1_carried.min_sequence_number = 1;
+        let m2 = write_manifest(
+            &file_io,
+            "s3://bucket/table/m2.avro",
+            456,
+            vec![data_file("s3://bucket/table/data/b.parquet", 20, 2000)],
+        )
+        .await;
+        write_manifest_list(
+            &file_io,
+            "s3://bucket/table/snap-456.avro",
+            456,
+            Some(123),
+            2,
+            vec![m1_carried, m2],
+        )
+        .await;
+
+        let table = load_table(&file_io, location).await?;
+        Ok((file_io, table))
+    }
+
+    async fn verify(
+        table: &Table,
+        all_snapshots: bool,
+        refs_only: bool,
+    ) -> (Result<()>, Vec<serde_json::Value>) {
+        let mut buffer = Vec::new();
+        let mut output = TerminalOutput::with_writer(&mut buffer);
+        let result = handle_table_command(
+            table,
+            TableCommands::Verify {
+                all_snapshots,
+                refs_only,
+            },
+            &mut output,
+            None,
+        )
+        .await;
+
+        let records = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        (result, records)
+    }
+
+    #[tokio::test]
+    async fn test_verify_all_snapshots() -> Result<()> {
+        let (_, table) = create_table(&["a.parquet", "b.parquet"]).await?;
+
+        let (result, records) = verify(&table, true, false).await;
+
+        result?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0],
+            serde_json::json!({
+                "kind": "summary",
+                "snapshots-checked": 2,
+                "manifest-lists-checked": 2,
+                // m1 is shared by both snapshots, but only checked once
+                "manifests-checked": 2,
+                "files-checked": 2,
+                "missing-files": 0,
+                "broken-snapshot-ids": [],
+                "broken-refs": []
+            })
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_broken_snapshots_and_refs() -> Result<()> {
+        let (file_io, table) = create_table(&["b.parquet"]).await?;
+        file_io.delete("s3://bucket/table/snap-456.avro").await?;
+
+        let (result, records) = verify(&table, true, false).await;
+
+        assert!(
+            result
+                .unwrap_err()
+                .downcast_ref::<ExpectedError>()
+                .is_some_and(|e| e.to_string() == "2 snapshot(s) broken by 2 missing file(s)")
+        );
+        assert_eq!(records.len(), 3);
+        assert_eq!(
+            records[0],
+            serde_json::json!({
+                "kind": "missing",
+                "type": "manifest-list",
+                "path": "s3://bucket/table/snap-456.avro",
+                "snapshot-ids": [456],
+                "refs": ["main"]
+            })
+        );
+        // Only reached through snapshot 123, since 456's manifest list is gone
+        assert_eq!(
+            records[1],
+            serde_json::json!({
+                "kind": "missing",
+                "type": "data",
+                "path": "s3://bucket/table/data/a.parquet",
+                "snapshot-ids": [123],
+                "refs": ["audit", "v1"]
+            })
+        );
+        assert_eq!(
+            records[2]["broken-snapshot-ids"],
+            serde_json::json!([123, 456])
+        );
+        assert_eq!(
+            records[2]["broken-refs"],
+            serde_json::json!(["audit", "main", "v1"])
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_current_and_refs_only() -> Result<()> {
+        let (file_io, table) = create_table(&["a.parquet", "b.parquet"]).await?;
+        file_io.delete("s3://bucket/table/m2.avro").await?;
+
+        let (result, records) = verify(&table, false, false).await;
+        assert!(result.is_err());
+        assert_eq!(records[0]["type"], "manifest");
+        assert_eq!(records[0]["snapshot-ids"], serde_json::json!([456]));
+        assert_eq!(records[1]["snapshots-checked"], 1);
+
+        let (result, records) = verify(&table, false, true).await;
+        assert!(result.is_err());
+        assert_eq!(records[1]["snapshots-checked"], 2);
+        assert_eq!(records[1]["broken-refs"], serde_json::json!(["main"]));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+//! Structured summary of a `files --verify` run, written with `--report`.
+//!
+//! Built up from the same records that are streamed to the terminal, so the
+//! report and the output always agree.
+
+use crate::table_commands::{FileRecord, FileType, SizeMismatch};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) struct VerificationReport {
+    snapshot_id: i64,
+    /// How file existence was checked, e.g. `file-io`, or `s3-listing+head` when files
+    /// outside the listed prefix were checked individually
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checker: Option<&'static str>,
+    checked_sizes: bool,
+    deep: bool,
+    duration_ms: u64,
+    files_checked: u64,
+    file_types: BTreeMap<FileType, FileTypeCounts>,
+    missing: Vec<String>,
+    size_mismatches: Vec<SizeMismatchEntry>,
+    deep_failures: Vec<DeepFailureEntry>,
+    /// Why verification stopped early, if it did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct FileTypeCounts {
+    checked: u64,
+    missing: u64,
+    size_mismatches: u64,
+    deep_failures: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct SizeMismatchEntry {
+    path: String,
+    #[serde(flatten)]
+    mismatch: SizeMismatch,
+}
+
+#[derive(Debug, Serialize)]
+struct DeepFailureEntry {
+    path: String,
+    failures: Vec<String>,
+}
+
+impl VerificationReport {
+    pub(crate) fn new(snapshot_id: i64, checked_sizes: bool, deep: bool) -> Self {
+        Self {
+            snapshot_id,
+            checker: None,
+            checked_sizes,
+            deep,
+            duration_ms: 0,
+            files_checked: 0,
+            file_types: BTreeMap::new(),
+            missing: Vec::new(),
+            size_mismatches: Vec::new(),
+            deep_failures: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Counts one verified file, noting any problems found with it.
+    pub(crate) fn add(&mut self, record: &FileRecord) {
+        self.files_checked += 1;
+        let counts = self.file_types.entry(record.r#type).or_default();
+        counts.checked += 1;
+
+        if record.exists == Some(false) {
+            counts.missing += 1;
+            self.missing.push(record.path.clone());
+        }
+        if let Some(mismatch) = &record.size_mismatch {
+            counts.size_mismatches += 1;
+            self.size_mismatches.push(SizeMismatchEntry {
+                path: record.path.clone(),
+                mismatch: mismatch.clone(),
+            });
+        }
+        if !record.failures.is_empty() {
+            counts.deep_failures += 1;
+            self.deep_failures.push(DeepFailureEntry {
+                path: record.path.clone(),
+                failures: record.failures.clone(),
+            });
+        }
+    }
+
+    pub(crate) fn missing_count(&self) -> usize {
+        self.missing.len()
+    }
+
+    pub(crate) fn size_mismatch_count(&self) -> usize {
+        self.size_mismatches.len()
+    }
+
+    pub(crate) fn deep_failure_count(&self) -> usize {
+        self.deep_failures.len()
+    }
+
+    /// Records how long verification took, and how the checker found files along the way.
+    pub(crate) fn finish(&mut self, duration: Duration, checker: Option<&'static str>) {
+        self.duration_ms = duration.as_millis() as u64;
+        self.checker = checker;
+    }
+
+    /// Records an error that stopped verification before every file was checked.
+    pub(crate) fn fail(&mut self, error: &anyhow::Error) {
+        self.error = Some(format!("{:#}", error));
+    }
+
+    /// Writes the report as pretty-printed JSON.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write report to {}", path.display()))
+    }
+}